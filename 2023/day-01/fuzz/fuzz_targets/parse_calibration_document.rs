@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let document = day_01::parse_calibration_document(input);
+
+    for value in document.iter() {
+        value.numbers().for_each(drop);
+    }
+});
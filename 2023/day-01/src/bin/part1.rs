@@ -1,11 +1,17 @@
 use anyhow::{Context, Result};
 
-use day_01::TaintedCalibrationValue;
+use day_01::{Dictionary, TaintedCalibrationValue};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -15,6 +21,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u32> {
     let document = day_01::parse_calibration_document(input);
+    timing::parsed();
 
     let result = document
         .iter()
@@ -27,8 +34,7 @@ fn process(input: &str) -> Result<u32> {
 #[must_use]
 fn get_number_value(tainted_value: &TaintedCalibrationValue) -> Option<u32> {
     let numbers = tainted_value
-        .chars()
-        .filter_map(|c| c.to_digit(10))
+        .numbers_with(Dictionary::digits())
         .collect::<Vec<_>>();
 
     let first = numbers.first()?;
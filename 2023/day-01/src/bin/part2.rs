@@ -3,9 +3,15 @@ use anyhow::{Context, Result};
 use day_01::TaintedCalibrationValue;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -15,6 +21,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u32> {
     let document = day_01::parse_calibration_document(input);
+    timing::parsed();
 
     let result = document
         .iter()
@@ -26,10 +33,8 @@ fn process(input: &str) -> Result<u32> {
 
 #[must_use]
 fn get_real_value(tainted_value: &TaintedCalibrationValue) -> Option<u32> {
-    let mut it = tainted_value.into_iter();
-
-    let first = it.next()?;
-    let last = it.last().map_or(first, |n| n);
+    let first = tainted_value.first_number()?;
+    let last = tainted_value.last_number()?;
 
     Some(first * 10 + last)
 }
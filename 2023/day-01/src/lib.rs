@@ -1,4 +1,14 @@
-use std::{fmt::Display, ops::Deref};
+use std::{
+    fmt::Display,
+    iter::Peekable,
+    ops::{Deref, Range},
+    sync::OnceLock,
+};
+
+use aho_corasick::{
+    automaton::Automaton, dfa::DFA, AhoCorasick, Anchored, FindOverlappingIter, MatchKind,
+};
+use anyhow::{ensure, Result};
 
 #[derive(Debug)]
 pub struct CalibrationDocument<'a>(Vec<TaintedCalibrationValue<'a>>);
@@ -15,9 +25,32 @@ impl<'a> Deref for CalibrationDocument<'a> {
 pub struct TaintedCalibrationValue<'a>(&'a str);
 
 impl<'a> TaintedCalibrationValue<'a> {
+    /// Numbers written with digits or English words, see [`Dictionary::english`].
     #[must_use]
     pub fn numbers(&self) -> NumberIterator<'a> {
-        NumberIterator(self)
+        self.numbers_with(Dictionary::english())
+    }
+
+    /// The first of [`Self::numbers`], without scanning the rest of the value.
+    #[must_use]
+    pub fn first_number(&self) -> Option<u32> {
+        Dictionary::english().first(self.0).map(|found| found.value)
+    }
+
+    /// The last of [`Self::numbers`], scanning backwards from the end.
+    #[must_use]
+    pub fn last_number(&self) -> Option<u32> {
+        Dictionary::english().last(self.0).map(|found| found.value)
+    }
+
+    #[must_use]
+    pub fn numbers_with(&self, dictionary: &'a Dictionary) -> NumberIterator<'a> {
+        NumberIterator(self.matches(dictionary))
+    }
+
+    #[must_use]
+    pub fn matches(&self, dictionary: &'a Dictionary) -> Matches<'a> {
+        dictionary.find_iter(self.0)
     }
 }
 
@@ -29,7 +62,7 @@ impl<'a> Deref for TaintedCalibrationValue<'a> {
     }
 }
 
-impl<'a> Display for TaintedCalibrationValue<'a> {
+impl Display for TaintedCalibrationValue<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(self.0)
     }
@@ -45,9 +78,70 @@ impl<'a> IntoIterator for TaintedCalibrationValue<'a> {
     }
 }
 
-pub struct NumberIterator<'a>(&'a str);
+/// A match of a dictionary entry inside a calibration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberMatch {
+    pub value: u32,
+    /// Byte span of the matched word in the calibration value.
+    pub span: Range<usize>,
+}
+
+/// Every dictionary match in a calibration value, overlapping ones included,
+/// in the order in which they end, shorter ones first when several end at
+/// the same byte.
+pub struct Matches<'a> {
+    values: &'a [u32],
+    inner: Peekable<FindOverlappingIter<'a, 'a>>,
+    /// Matches ending at the same byte, the next one to yield last
+    pending: Vec<NumberMatch>,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = NumberMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(found) = self.pending.pop() {
+            return Some(found);
+        }
+
+        let end = self.inner.peek()?.end();
+
+        while let Some(found) = self.inner.next_if(|found| found.end() == end) {
+            self.pending.push(NumberMatch {
+                value: self.values[found.pattern().as_usize()],
+                span: found.range(),
+            });
+        }
+
+        self.pending.sort_unstable_by_key(|found| found.span.start);
+        self.pending.pop()
+    }
+}
+
+pub struct NumberIterator<'a>(Matches<'a>);
 
-const NUMBER_TRANSLATOR: [(&str, u32); 9] = [
+impl Iterator for NumberIterator<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|found| found.value)
+    }
+}
+
+pub const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+pub const ENGLISH: [(&str, u32); 9] = [
     ("one", 1),
     ("two", 2),
     ("three", 3),
@@ -59,27 +153,397 @@ const NUMBER_TRANSLATOR: [(&str, u32); 9] = [
     ("nine", 9),
 ];
 
-impl<'a> Iterator for NumberIterator<'a> {
-    type Item = u32;
+/// Words that stand for numbers in a calibration value.
+///
+/// They are matched all at once with an Aho-Corasick automaton. The same
+/// words in a DFA find the first number without searching past it, and a
+/// leftmost-longest automaton over the reversed words finds the last number
+/// by scanning from the end.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    automaton: AhoCorasick,
+    forward: DFA,
+    reversed: DFA,
+    values: Vec<u32>,
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        for (num, n) in NUMBER_TRANSLATOR {
-            if self.0.starts_with(num) {
-                // NOTE: Use len - 1 because of oneight, it should be counted as 18
-                self.0 = &self.0[num.len() - 1..];
-                return Some(n);
+impl Dictionary {
+    /// Builds a dictionary from `(word, value)` entries.
+    ///
+    /// # Errors
+    /// Errors if a word is empty or the automaton cannot be built
+    pub fn new<I, S>(entries: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (S, u32)>,
+        S: AsRef<str>,
+    {
+        let (words, values): (Vec<S>, Vec<u32>) = entries.into_iter().unzip();
+
+        ensure!(
+            words.iter().all(|word| !word.as_ref().is_empty()),
+            "Dictionary words must not be empty"
+        );
+
+        let automaton = AhoCorasick::new(words.iter().map(AsRef::as_ref))?;
+        let forward = DFA::new(words.iter().map(AsRef::as_ref))?;
+        let reversed = DFA::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(
+                words
+                    .iter()
+                    .map(|word| word.as_ref().bytes().rev().collect::<Vec<_>>()),
+            )?;
+
+        Ok(Self {
+            automaton,
+            forward,
+            reversed,
+            values,
+        })
+    }
+
+    /// Only the digits `0` to `9`.
+    #[must_use]
+    pub fn digits() -> &'static Self {
+        static DICTIONARY: OnceLock<Dictionary> = OnceLock::new();
+
+        DICTIONARY.get_or_init(|| Self::from_static(DIGITS.iter().copied()))
+    }
+
+    /// The digits together with the English words `one` to `nine`.
+    #[must_use]
+    pub fn english() -> &'static Self {
+        static DICTIONARY: OnceLock<Dictionary> = OnceLock::new();
+
+        DICTIONARY.get_or_init(|| Self::from_static(DIGITS.iter().chain(&ENGLISH).copied()))
+    }
+
+    fn from_static(entries: impl Iterator<Item = (&'static str, u32)>) -> Self {
+        Self::new(entries).expect("Built-in dictionaries are valid")
+    }
+
+    /// The first match of [`Self::find_iter`], the one that ends first and
+    /// the shortest of those ending there. The automaton stops at the first
+    /// byte ending a word, whose state holds every word ending there.
+    #[must_use]
+    pub fn first(&self, haystack: &str) -> Option<NumberMatch> {
+        let automaton = &self.forward;
+        let mut state = automaton.start_state(Anchored::No).ok()?;
+
+        for (end, &byte) in haystack.as_bytes().iter().enumerate() {
+            state = automaton.next_state(Anchored::No, state, byte);
+
+            if automaton.is_special(state) && automaton.is_match(state) {
+                let pattern = (0..automaton.match_len(state))
+                    .map(|index| automaton.match_pattern(state, index))
+                    .min_by_key(|&pattern| automaton.pattern_len(pattern))?;
+
+                return Some(NumberMatch {
+                    value: self.values[pattern.as_usize()],
+                    span: end + 1 - automaton.pattern_len(pattern)..end + 1,
+                });
             }
         }
 
-        let next = self.0.chars().next()?;
-        self.0 = &self.0[1..];
-        next.to_digit(10).map_or_else(|| self.next(), Some)
+        None
+    }
+
+    /// The last match of [`Self::find_iter`], the one that ends last and the
+    /// longest of those ending there. Its reversed word is the leftmost
+    /// longest match when feeding the bytes from the end into the reversed
+    /// automaton, which stops as soon as no longer match is possible.
+    #[must_use]
+    pub fn last(&self, haystack: &str) -> Option<NumberMatch> {
+        let automaton = &self.reversed;
+        let mut state = automaton.start_state(Anchored::No).ok()?;
+        let mut found = None;
+
+        for (start, &byte) in haystack.as_bytes().iter().enumerate().rev() {
+            state = automaton.next_state(Anchored::No, state, byte);
+
+            if automaton.is_special(state) {
+                if automaton.is_dead(state) {
+                    break;
+                }
+
+                if automaton.is_match(state) {
+                    let pattern = automaton.match_pattern(state, 0);
+
+                    found = Some(NumberMatch {
+                        value: self.values[pattern.as_usize()],
+                        span: start..start + automaton.pattern_len(pattern),
+                    });
+                }
+            }
+        }
+
+        found
+    }
+
+    #[must_use]
+    pub fn find_iter<'a>(&'a self, haystack: &'a str) -> Matches<'a> {
+        Matches {
+            values: &self.values,
+            inner: self.automaton.find_overlapping_iter(haystack).peekable(),
+            pending: Vec::new(),
+        }
     }
 }
 
 #[must_use]
-pub fn parse_calibration_document(input: &str) -> CalibrationDocument {
+pub fn parse_calibration_document(input: &str) -> CalibrationDocument<'_> {
     let documents = input.lines().map(TaintedCalibrationValue).collect();
 
     CalibrationDocument(documents)
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn spans(value: &str, dictionary: &Dictionary) -> Vec<(u32, Range<usize>)> {
+        TaintedCalibrationValue(value)
+            .matches(dictionary)
+            .map(|found| (found.value, found.span))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("oneight", vec![(1, 0..3), (8, 2..7)])]
+    #[case("twone3", vec![(2, 0..3), (1, 2..5), (3, 5..6)])]
+    #[case("7pqrstsixteen", vec![(7, 0..1), (6, 6..9)])]
+    #[case("xyz", vec![])]
+    fn test_english_matches(#[case] value: &str, #[case] expected: Vec<(u32, Range<usize>)>) {
+        assert_eq!(expected, spans(value, Dictionary::english()));
+    }
+
+    #[test]
+    fn test_digits_ignore_words() {
+        assert_eq!(vec![(2, 3..4)], spans("one2", Dictionary::digits()));
+    }
+
+    #[test]
+    fn test_custom_dictionary() -> Result<()> {
+        let dictionary = Dictionary::new(
+            DIGITS
+                .iter()
+                .chain(&ENGLISH)
+                .copied()
+                .chain([("zero", 0), ("ten", 10)]),
+        )?;
+
+        let numbers = TaintedCalibrationValue("zerotenine")
+            .numbers_with(&dictionary)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![0, 10, 9], numbers);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_language() -> Result<()> {
+        let dictionary = Dictionary::new([("eins", 1), ("zwei", 2), ("drei", 3)])?;
+
+        assert_eq!(
+            vec![(2, 0..4), (1, 5..9), (3, 9..13)],
+            spans("zweiseinsdrei", &dictionary)
+        );
+
+        Ok(())
+    }
+
+    const OVERLAPS: [&str; 12] = [
+        "oneight",
+        "twone",
+        "threeight",
+        "fiveight",
+        "sevenine",
+        "eightwo",
+        "eighthree",
+        "nineight",
+        "oneightwoneight",
+        "1twone",
+        "eightwo2",
+        "xtwone3four",
+    ];
+
+    /// Words nested inside other words, ending together or not
+    const NESTED: [(&str, u32); 7] = [
+        ("nine", 9),
+        ("nineteen", 19),
+        ("teen", 13),
+        ("ten", 10),
+        ("eleven", 11),
+        ("even", 8),
+        ("n", 0),
+    ];
+
+    fn assert_agrees_with(value: &str, dictionary: &Dictionary) {
+        let matches = dictionary.find_iter(value).collect::<Vec<_>>();
+
+        assert_eq!(matches.first(), dictionary.first(value).as_ref(), "{value}");
+        assert_eq!(matches.last(), dictionary.last(value).as_ref(), "{value}");
+    }
+
+    fn assert_agrees(value: &str) {
+        assert_agrees_with(value, Dictionary::english());
+
+        let value = TaintedCalibrationValue(value);
+
+        assert_eq!(value.numbers().next(), value.first_number(), "{value}");
+        assert_eq!(value.numbers().last(), value.last_number(), "{value}");
+    }
+
+    #[test]
+    fn test_first_and_last_agree_with_iterator() {
+        OVERLAPS
+            .into_iter()
+            .chain([
+                "",
+                "abc",
+                "7",
+                "zoneight234",
+                "4nineeightseven2",
+                "éightwoé",
+            ])
+            .for_each(assert_agrees);
+    }
+
+    #[test]
+    fn test_first_and_last_agree_on_every_overlapping_pair() {
+        let words = DIGITS.iter().chain(&ENGLISH).map(|(word, _)| *word);
+
+        for first in words.clone() {
+            for second in words.clone() {
+                assert_agrees(&format!("{first}{second}"));
+                assert_agrees(&format!("x{first}y{second}z"));
+
+                for overlap in 1..first.len().min(second.len()) {
+                    if first.ends_with(&second[..overlap]) {
+                        assert_agrees(&format!("{first}{}", &second[overlap..]));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_last_of_custom_dictionary() -> Result<()> {
+        let dictionary = Dictionary::new([("zero", 0), ("ten", 10), ("one", 1)])?;
+
+        let found = dictionary.last("zerotenone");
+
+        assert_eq!(
+            Some(NumberMatch {
+                value: 1,
+                span: 7..10
+            }),
+            found
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("nineteen", vec![(0, 0..1), (0, 2..3), (9, 0..4), (0, 7..8), (13, 4..8), (19, 0..8)])]
+    #[case("eleven", vec![(0, 5..6), (8, 2..6), (11, 0..6)])]
+    #[case("televenteen", vec![(0, 6..7), (8, 3..7), (11, 1..7), (0, 10..11), (13, 7..11)])]
+    fn test_nested_words(
+        #[case] value: &str,
+        #[case] expected: Vec<(u32, Range<usize>)>,
+    ) -> Result<()> {
+        let dictionary = Dictionary::new(NESTED)?;
+
+        assert_eq!(expected, spans(value, &dictionary));
+        assert_agrees_with(value, &dictionary);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_and_last_agree_with_nested_words() -> Result<()> {
+        let dictionary = Dictionary::new(NESTED)?;
+
+        for first in NESTED {
+            for second in NESTED {
+                for separator in ["", "x", "e", "n"] {
+                    assert_agrees_with(&format!("{}{separator}{}", first.0, second.0), &dictionary);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_word() {
+        assert!(Dictionary::new([("", 0)]).is_err());
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        assert_eq!(vec![(1, 2..5)], spans("éone", Dictionary::english()));
+    }
+
+    /// Words, digits and pieces of words that can overlap when put together
+    const FRAGMENTS: [&str; 28] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "1", "2", "3", "4",
+        "5", "6", "7", "8", "9", "0", "on", "tw", "ight", "ine", "ree", "x", "e", "n", "t",
+    ];
+
+    /// Generates a calibration value out of fragments, so numbers often overlap
+    fn value() -> impl Strategy<Value = String> {
+        prop::collection::vec(prop::sample::select(&FRAGMENTS[..]), 0..12)
+            .prop_map(|fragments| fragments.concat())
+    }
+
+    /// Generates a value that is not empty, as each line of a document
+    fn line() -> impl Strategy<Value = String> {
+        value().prop_filter("Lines are not empty", |value| !value.is_empty())
+    }
+
+    /// Every word ending at each position, shortest first
+    fn naive_spans(value: &str, dictionary: &[(&str, u32)]) -> Vec<(u32, Range<usize>)> {
+        let mut words = dictionary.to_vec();
+        words.sort_by_key(|(word, _)| word.len());
+
+        (1..=value.len())
+            .flat_map(|end| {
+                words
+                    .iter()
+                    .filter(move |(word, _)| value[..end].ends_with(word))
+                    .map(move |(word, number)| (*number, end - word.len()..end))
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_agree_with_naive_search(value in value()) {
+            let english = DIGITS.iter().chain(&ENGLISH).copied().collect::<Vec<_>>();
+            let nested = Dictionary::new(NESTED).expect("Nested words are valid");
+
+            prop_assert_eq!(naive_spans(&value, &english), spans(&value, Dictionary::english()));
+            prop_assert_eq!(naive_spans(&value, &DIGITS), spans(&value, Dictionary::digits()));
+            prop_assert_eq!(naive_spans(&value, &NESTED), spans(&value, &nested));
+        }
+
+        #[test]
+        fn test_first_and_last_agree_on_generated_values(value in value()) {
+            assert_agrees(&value);
+            assert_agrees_with(&value, &Dictionary::new(NESTED).expect("Nested words are valid"));
+        }
+
+        #[test]
+        fn test_parse_calibration_document(values in prop::collection::vec(line(), 1..10)) {
+            let input = values.join("\n");
+            let document = parse_calibration_document(&input);
+
+            prop_assert_eq!(values, document.iter().map(ToString::to_string).collect::<Vec<_>>());
+        }
+    }
+}
@@ -1,10 +1,16 @@
 use anyhow::Result;
-use day_02::Bag;
+use day_02::{Bag, CubeConundrum};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -16,12 +22,9 @@ fn process(input: &str) -> Result<u32> {
     let bag = Bag::new(12, 13, 14);
 
     let games = day_02::parse_games(input)?;
+    timing::parsed();
 
-    let result = games
-        .into_iter()
-        .filter(|g| g.iter().all(|b| b.is_contained(&bag)))
-        .map(|g| g.get_id())
-        .sum();
+    let result = games.possible_with(&bag).map(CubeConundrum::get_id).sum();
 
     Ok(result)
 }
@@ -1,9 +1,15 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,8 +19,9 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u32> {
     let games = day_02::parse_games(input)?;
+    timing::parsed();
 
-    Ok(games.into_iter().map(|g| g.minimum_bag().power()).sum())
+    Ok(games.iter().map(|g| games.power(&g.minimum_bag())).sum())
 }
 
 #[cfg(test)]
@@ -1,6 +1,61 @@
-use std::ops::Deref;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Deref,
+};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{ensure, Context, Result};
+
+/// All the games of an input, together with every colour seen in them
+#[derive(Debug, PartialEq, Eq)]
+pub struct Games {
+    palette: BTreeSet<String>,
+    games: Vec<CubeConundrum>,
+}
+
+impl Deref for Games {
+    type Target = Vec<CubeConundrum>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.games
+    }
+}
+
+impl IntoIterator for Games {
+    type Item = CubeConundrum;
+
+    type IntoIter = std::vec::IntoIter<CubeConundrum>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.games.into_iter()
+    }
+}
+
+impl Games {
+    /// Every colour that appears in any game
+    #[must_use]
+    pub const fn palette(&self) -> &BTreeSet<String> {
+        &self.palette
+    }
+
+    /// The games that could have been played with `bag`
+    pub fn possible_with<'a>(&'a self, bag: &'a Bag) -> impl Iterator<Item = &'a CubeConundrum> {
+        self.iter().filter(|game| game.is_possible_with(bag))
+    }
+
+    /// The smallest bag with which every game is possible
+    #[must_use]
+    pub fn smallest_bag(&self) -> Bag {
+        self.iter()
+            .fold(Bag::default(), |bag, game| bag.maximum(&game.minimum_bag()))
+    }
+
+    /// The power of `bag` over the whole palette, so colours missing from the
+    /// bag count as zero
+    #[must_use]
+    pub fn power(&self, bag: &Bag) -> u32 {
+        bag.power(self.palette.iter().map(String::as_str))
+    }
+}
 
 /// A game of the Cube Conundrum
 /// Each game consists of an id and successive cubes pulled out of the bag
@@ -27,50 +82,96 @@ impl CubeConundrum {
         self.iter()
             .fold(Bag::default(), |bag, other| bag.maximum(other))
     }
+
+    #[must_use]
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.iter().all(|pull| pull.is_contained(bag))
+    }
+
+    /// The colour of the game with the least headroom in `bag`, and that
+    /// headroom. A negative headroom means the game is impossible with `bag`.
+    /// Ties go to the colour first in alphabetical order.
+    #[must_use]
+    pub fn limiting_color(&self, bag: &Bag) -> Option<(String, i64)> {
+        self.minimum_bag()
+            .iter()
+            .map(|(color, n)| (color, i64::from(bag.get(color)) - i64::from(n)))
+            .min_by_key(|(_, headroom)| *headroom)
+            .map(|(color, headroom)| (color.to_string(), headroom))
+    }
 }
 
 /// A bag from the Cube Conundrum game
-/// Each bag has an ammount of red green and blue cubes
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct Bag {
-    red: u32,
-    green: u32,
-    blue: u32,
+/// Each bag has an ammount of cubes of any number of colors, colors without
+/// cubes are not stored
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bag(BTreeMap<String, u32>);
+
+impl<S: Into<String>> FromIterator<(S, u32)> for Bag {
+    fn from_iter<T: IntoIterator<Item = (S, u32)>>(iter: T) -> Self {
+        let mut bag = Self::default();
+
+        for (color, n) in iter {
+            bag.set(color, n);
+        }
+
+        bag
+    }
 }
 
 impl Bag {
+    /// A bag with the classic red, green and blue cubes
     #[must_use]
-    pub const fn new(red: u32, green: u32, blue: u32) -> Self {
-        Self { red, green, blue }
+    pub fn new(red: u32, green: u32, blue: u32) -> Self {
+        [("red", red), ("green", green), ("blue", blue)]
+            .into_iter()
+            .collect()
     }
 
+    /// The number of cubes of `color`, zero if the bag has none
     #[must_use]
-    pub const fn is_contained(&self, other: &Self) -> bool {
-        self.red <= other.red && self.green <= other.green && self.blue <= other.blue
+    pub fn get(&self, color: &str) -> u32 {
+        self.0.get(color).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, color: impl Into<String>, n: u32) {
+        let color = color.into();
+
+        if n == 0 {
+            self.0.remove(&color);
+        } else {
+            self.0.insert(color, n);
+        }
+    }
+
+    /// The colors in the bag and their number of cubes
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.0.iter().map(|(color, n)| (color.as_str(), *n))
+    }
+
+    #[must_use]
+    pub fn is_contained(&self, other: &Self) -> bool {
+        self.iter().all(|(color, n)| n <= other.get(color))
     }
 
     #[must_use]
     pub fn maximum(&self, other: &Self) -> Self {
-        Self {
-            red: self.red.max(other.red),
-            green: self.green.max(other.green),
-            blue: self.blue.max(other.blue),
+        let mut bag = self.clone();
+
+        for (color, n) in other.iter() {
+            bag.set(color, n.max(self.get(color)));
         }
+
+        bag
     }
 
+    /// The product of the cubes of each of `colors`
     #[must_use]
-    pub const fn power(&self) -> u32 {
-        self.red * self.green * self.blue
+    pub fn power<'a>(&self, colors: impl IntoIterator<Item = &'a str>) -> u32 {
+        colors.into_iter().map(|color| self.get(color)).product()
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum Color {
-    Red,
-    Green,
-    Blue,
-}
-
 /// Parses a List of Cube Conundrum games
 ///
 /// Each game is an id and then a list of plays taking boxes out of a bag
@@ -86,8 +187,16 @@ pub enum Color {
 /// # Errors
 ///
 /// The funcion can fail if the string does not follow the example
-pub fn parse_games(games: &str) -> Result<Vec<CubeConundrum>> {
-    games.lines().map(parse_game).collect()
+pub fn parse_games(games: &str) -> Result<Games> {
+    let games = games.lines().map(parse_game).collect::<Result<Vec<_>>>()?;
+
+    let palette = games
+        .iter()
+        .flat_map(|game| game.iter())
+        .flat_map(|bag| bag.0.keys().cloned())
+        .collect();
+
+    Ok(Games { palette, games })
 }
 
 /// Parses a Cube Conundrum game
@@ -123,11 +232,7 @@ fn parse_bag(cubes: &str) -> Result<Bag> {
     for cubes in colored_cubes {
         let (color, n) = cubes?;
 
-        match color {
-            Color::Red => bag.red = n,
-            Color::Green => bag.green = n,
-            Color::Blue => bag.blue = n,
-        }
+        bag.set(color, n);
     }
 
     Ok(bag)
@@ -139,18 +244,16 @@ fn parse_bag(cubes: &str) -> Result<Bag> {
 /// 3 blue
 ///
 /// Returns: a tuple of (color, quantity)
-fn parse_colored_cubes(cubes: &str) -> Result<(Color, u32)> {
+fn parse_colored_cubes(cubes: &str) -> Result<(&str, u32)> {
     let (n, color) = cubes
         .trim()
         .split_once(' ')
         .context("Cubes string should only have one space")?;
 
-    let color = match color {
-        "red" => Color::Red,
-        "green" => Color::Green,
-        "blue" => Color::Blue,
-        _ => bail!("Invalid color string"),
-    };
+    ensure!(
+        !color.is_empty() && !color.contains(char::is_whitespace),
+        "Invalid color string"
+    );
 
     let n: u32 = n.parse().context("Should be a number")?;
 
@@ -159,6 +262,8 @@ fn parse_colored_cubes(cubes: &str) -> Result<(Color, u32)> {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -169,7 +274,7 @@ mod tests {
 
         let (color, n) = result;
 
-        assert_eq!(Color::Blue, color);
+        assert_eq!("blue", color);
         assert_eq!(3, n);
     }
 
@@ -222,4 +327,192 @@ mod tests {
 
         assert_eq!(expected, bag);
     }
+
+    const GAMES: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_any_color() -> Result<()> {
+        let games = parse_games("Game 1: 3 purple, 4 red; 2 teal\nGame 2: 1 teal")?;
+
+        let expected: BTreeSet<String> = ["purple", "red", "teal"].map(String::from).into();
+
+        assert_eq!(&expected, games.palette());
+        assert_eq!(
+            Bag::from_iter([("purple", 3), ("red", 4), ("teal", 2)]),
+            games.smallest_bag()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_color() {
+        assert!(parse_colored_cubes("3 ").is_err());
+        assert!(parse_colored_cubes("3 dark red").is_err());
+    }
+
+    #[test]
+    fn test_possible_with() -> Result<()> {
+        let games = parse_games(GAMES)?;
+        let bag = Bag::new(12, 13, 14);
+
+        let possible = games
+            .possible_with(&bag)
+            .map(CubeConundrum::get_id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 2, 5], possible);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_smallest_bag() -> Result<()> {
+        let games = parse_games(GAMES)?;
+
+        assert_eq!(Bag::new(20, 13, 15), games.smallest_bag());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_power_counts_missing_colors() -> Result<()> {
+        let games = parse_games(GAMES)?;
+
+        assert_eq!(48, games.power(&Bag::new(4, 2, 6)));
+        assert_eq!(0, games.power(&Bag::new(4, 0, 6)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limiting_color() -> Result<()> {
+        let games = parse_games(GAMES)?;
+        let bag = Bag::new(12, 13, 14);
+
+        let limits = games
+            .iter()
+            .map(|game| game.limiting_color(&bag))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Some(("blue".to_string(), 8)),
+                Some(("blue".to_string(), 10)),
+                Some(("red".to_string(), -8)),
+                Some(("red".to_string(), -2)),
+                Some(("red".to_string(), 6)),
+            ],
+            limits
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limiting_color_tie() -> Result<()> {
+        let bag = Bag::new(12, 13, 12);
+
+        for game in [
+            "Game 1: 3 red, 3 blue",
+            "Game 1: 3 blue, 3 red",
+            "Game 1: 3 red; 1 green, 3 blue; 2 red",
+        ] {
+            let games = parse_games(game)?;
+
+            assert_eq!(Some(("blue".to_string(), 9)), games[0].limiting_color(&bag));
+        }
+
+        Ok(())
+    }
+
+    const COLORS: [&str; 5] = ["red", "green", "blue", "yellow", "magenta"];
+
+    /// Generates a pull, each colour at most once and never zero cubes
+    fn pull() -> impl Strategy<Value = Vec<(&'static str, u32)>> {
+        prop::sample::subsequence(&COLORS[..], 1..=COLORS.len())
+            .prop_flat_map(|colors| {
+                let counts = prop::collection::vec(1..30_u32, colors.len());
+                (Just(colors), counts)
+            })
+            .prop_map(|(colors, counts)| colors.into_iter().zip(counts).collect())
+    }
+
+    /// Generates the games of a record, as pulls with numbered ids
+    fn games() -> impl Strategy<Value = Vec<Vec<Vec<(&'static str, u32)>>>> {
+        prop::collection::vec(prop::collection::vec(pull(), 1..6), 1..10)
+    }
+
+    fn games_input(games: &[Vec<Vec<(&str, u32)>>]) -> String {
+        games
+            .iter()
+            .enumerate()
+            .map(|(id, pulls)| {
+                let pulls = pulls
+                    .iter()
+                    .map(|pull| {
+                        pull.iter()
+                            .map(|(color, n)| format!("{n} {color}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ");
+
+                format!("Game {}: {pulls}", id + 1)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_games(generated in games()) {
+            let games = parse_games(&games_input(&generated)).expect("Generated games are valid");
+
+            prop_assert_eq!(generated.len(), games.len());
+
+            for (id, (game, pulls)) in (1..).zip(games.iter().zip(&generated)) {
+                let expected = pulls
+                    .iter()
+                    .map(|pull| pull.iter().copied().collect())
+                    .collect::<Vec<Bag>>();
+
+                prop_assert_eq!(id, game.get_id());
+                prop_assert_eq!(&expected, &**game);
+            }
+        }
+
+        #[test]
+        fn test_minimum_bag_is_tight(generated in games()) {
+            let games = parse_games(&games_input(&generated)).expect("Generated games are valid");
+
+            for game in games.iter() {
+                let minimum = game.minimum_bag();
+
+                prop_assert!(game.is_possible_with(&minimum));
+
+                for (color, n) in minimum.iter() {
+                    let mut smaller = minimum.clone();
+                    smaller.set(color, n - 1);
+
+                    prop_assert!(!game.is_possible_with(&smaller));
+                }
+            }
+        }
+
+        #[test]
+        fn test_smallest_bag_fits_every_game(generated in games()) {
+            let games = parse_games(&games_input(&generated)).expect("Generated games are valid");
+            let smallest = games.smallest_bag();
+
+            prop_assert_eq!(games.len(), games.possible_with(&smallest).count());
+            prop_assert!(games.iter().all(|game| game.minimum_bag().is_contained(&smallest)));
+            prop_assert!(games.palette().iter().all(|color| smallest.get(color) > 0));
+        }
+    }
 }
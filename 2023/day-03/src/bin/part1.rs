@@ -2,9 +2,15 @@ use anyhow::{Ok, Result};
 use day_03::parse_schematic;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -14,11 +20,12 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let schematic = parse_schematic(input)?;
+    timing::parsed();
 
     let result = schematic
-        .parts
+        .parts()
         .iter()
-        .filter(|p| p.is_valid(&schematic.symbols))
+        .filter(|p| schematic.is_valid(p))
         .map(|p| p.id)
         .sum();
 
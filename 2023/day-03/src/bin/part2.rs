@@ -1,9 +1,16 @@
 use anyhow::{Ok, Result};
+use day_03::GearRule;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,11 +20,12 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let schematic = day_03::parse_schematic(input)?;
+    timing::parsed();
 
     let result = schematic
-        .symbols
+        .symbols()
         .iter()
-        .filter_map(|p| p.gear_ratio(&schematic.parts))
+        .filter_map(|p| schematic.gear_ratio(p, &GearRule::default()))
         .sum();
 
     Ok(result)
@@ -42,6 +50,6 @@ mod tests {
 
         let result = process(input).expect("Process failure");
 
-        assert_eq!(467835, result);
+        assert_eq!(467_835, result);
     }
 }
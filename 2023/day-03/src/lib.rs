@@ -1,11 +1,15 @@
-use std::{collections::HashSet, num::TryFromIntError};
+use std::{collections::HashMap, ops::RangeInclusive};
 
 use anyhow::{Context, Result};
 
+/// Engine schematic, indexed by position so adjacency queries only look at
+/// the neighbouring cells
 #[derive(Debug)]
 pub struct Schematic {
-    pub parts: Vec<PartNumber>,
-    pub symbols: Vec<Symbol>,
+    parts: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+    part_at: HashMap<Position, usize>,
+    symbol_at: HashMap<Position, usize>,
 }
 
 #[derive(Debug)]
@@ -32,34 +36,30 @@ impl From<(usize, usize)> for Position {
     }
 }
 
-impl TryFrom<(i128, i128)> for Position {
-    type Error = TryFromIntError;
+impl Position {
+    /// The up to eight positions around this one
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        let Self { x, y } = self;
 
-    fn try_from((x, y): (i128, i128)) -> Result<Self, Self::Error> {
-        Ok(Self {
-            x: x.try_into()?,
-            y: y.try_into()?,
-        })
+        [x.checked_sub(1), Some(x), x.checked_add(1)]
+            .into_iter()
+            .flatten()
+            .flat_map(move |nx| {
+                [y.checked_sub(1), Some(y), y.checked_add(1)]
+                    .into_iter()
+                    .flatten()
+                    .map(move |ny| Self { x: nx, y: ny })
+            })
+            .filter(move |&position| position != self)
     }
 }
 
 impl PartNumber {
-    fn new(id: u64, x: usize, y: usize) -> Self {
-        let lenght = id.to_string().len();
-
-        assert!(x >= lenght, "lenght {lenght}, y {x}");
-
-        let positions = (x - lenght..x).map(|x| (x, y).into()).collect();
+    fn new(id: u64, xs: std::ops::Range<usize>, y: usize) -> Self {
+        let positions = xs.map(|x| (x, y).into()).collect();
 
         Self { id, positions }
     }
-
-    #[must_use]
-    pub fn is_valid(&self, symbols: &[Symbol]) -> bool {
-        let adj = adjacent_positions(&self.positions);
-
-        symbols.iter().any(|s| adj.contains(&s.position))
-    }
 }
 
 impl Symbol {
@@ -69,74 +69,189 @@ impl Symbol {
             position: (x, y).into(),
         }
     }
+}
 
+impl Schematic {
     #[must_use]
-    pub fn gear_ratio(&self, parts: &[PartNumber]) -> Option<u64> {
-        if self.symbol != '*' {
-            // NOTE: if symbol is not *, its not a gear
-            return None;
-        }
-
-        let adj = adjacent_positions(&[self.position]);
+    pub fn new(parts: Vec<PartNumber>, symbols: Vec<Symbol>) -> Self {
+        let part_at = parts
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, part)| part.positions.iter().map(move |&p| (p, idx)))
+            .collect();
 
-        let contact_parts: Vec<u64> = parts
+        let symbol_at = symbols
             .iter()
-            .filter_map(|part| {
-                if part.positions.iter().any(|p| adj.contains(p)) {
-                    Some(part.id)
-                } else {
-                    None
-                }
-            })
+            .enumerate()
+            .map(|(idx, symbol)| (symbol.position, idx))
             .collect();
 
-        if contact_parts.len() != 2 {
-            // NOTE: if not two connecting parts, its not a gear
-            return None;
+        Self {
+            parts,
+            symbols,
+            part_at,
+            symbol_at,
         }
+    }
+
+    #[must_use]
+    pub fn parts(&self) -> &[PartNumber] {
+        &self.parts
+    }
+
+    #[must_use]
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    #[must_use]
+    pub fn part_at(&self, position: Position) -> Option<&PartNumber> {
+        self.part_at.get(&position).map(|&idx| &self.parts[idx])
+    }
+
+    #[must_use]
+    pub fn symbol_at(&self, position: Position) -> Option<&Symbol> {
+        self.symbol_at.get(&position).map(|&idx| &self.symbols[idx])
+    }
+
+    /// Each part touching `symbol`, once even if it touches it with several
+    /// digits
+    #[must_use]
+    pub fn parts_adjacent_to(&self, symbol: &Symbol) -> Vec<&PartNumber> {
+        let mut idxs = symbol
+            .position
+            .neighbours()
+            .filter_map(|p| self.part_at.get(&p).copied())
+            .collect::<Vec<_>>();
+
+        idxs.sort_unstable();
+        idxs.dedup();
+
+        idxs.into_iter().map(|idx| &self.parts[idx]).collect()
+    }
+
+    /// Each symbol touching any digit of `part`
+    #[must_use]
+    pub fn symbols_adjacent_to(&self, part: &PartNumber) -> Vec<&Symbol> {
+        let mut idxs = part
+            .positions
+            .iter()
+            .flat_map(|p| p.neighbours())
+            .filter_map(|p| self.symbol_at.get(&p).copied())
+            .collect::<Vec<_>>();
+
+        idxs.sort_unstable();
+        idxs.dedup();
 
-        Some(contact_parts.iter().product())
+        idxs.into_iter().map(|idx| &self.symbols[idx]).collect()
+    }
+
+    /// A part is valid when a symbol is adjacent to it
+    #[must_use]
+    pub fn is_valid(&self, part: &PartNumber) -> bool {
+        part.positions
+            .iter()
+            .flat_map(|p| p.neighbours())
+            .any(|p| self.symbol_at.contains_key(&p))
+    }
+
+    /// The ratio of `symbol` under `rule`, if it qualifies as a gear
+    #[must_use]
+    pub fn gear_ratio(&self, symbol: &Symbol, rule: &GearRule) -> Option<u64> {
+        rule.ratio(symbol, &self.parts_adjacent_to(symbol))
+    }
+
+    /// For every symbol, its adjacent parts and its ratio under `rule`
+    pub fn symbol_reports<'a>(
+        &'a self,
+        rule: &'a GearRule,
+    ) -> impl Iterator<Item = SymbolReport<'a>> + 'a {
+        self.symbols.iter().map(|symbol| {
+            let parts = self.parts_adjacent_to(symbol);
+            let qualifies = rule.qualifies(symbol, &parts);
+            let ratio = rule.ratio(symbol, &parts);
+
+            SymbolReport {
+                symbol,
+                parts,
+                qualifies,
+                ratio,
+            }
+        })
     }
 }
 
-#[must_use]
-fn adjacent_positions(positions: &[Position]) -> HashSet<Position> {
-    let west_idx = 0;
-    let east_idx = positions.len() - 1;
+/// How the ids of the parts around a gear are combined into its ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
 
-    let mut set: HashSet<Position> = HashSet::with_capacity(6 + 2 * positions.len());
+/// What makes a symbol a gear: the symbol itself, how many parts must be
+/// adjacent to it and how their ids make the ratio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbol: char,
+    pub parts: RangeInclusive<usize>,
+    pub combine: Combine,
+}
 
-    for (idx, position) in positions.iter().enumerate() {
-        // NOTE: Cast positions as signed to prevent panics
-        let (x, y) = (position.x as i128, position.y as i128);
+impl Default for GearRule {
+    /// A `*` with exactly two parts, whose ratio is their product
+    fn default() -> Self {
+        Self::exactly('*', 2, Combine::Product)
+    }
+}
 
-        // NOTE: In every position add north and south borders
-        set.extend(
-            [(x, y + 1), (x, y - 1)]
-                .into_iter()
-                .filter_map(|p| TryInto::<Position>::try_into(p).ok()),
-        );
+impl GearRule {
+    #[must_use]
+    pub const fn new(symbol: char, parts: RangeInclusive<usize>, combine: Combine) -> Self {
+        Self {
+            symbol,
+            parts,
+            combine,
+        }
+    }
 
-        // NOTE: On the first position add west border
-        if idx == west_idx {
-            set.extend(
-                [(x - 1, y), (x - 1, y + 1), (x - 1, y - 1)]
-                    .into_iter()
-                    .filter_map(|p| TryInto::<Position>::try_into(p).ok()),
-            );
-        };
-
-        // NOTE: On the last position add east border
-        if idx == east_idx {
-            set.extend(
-                [(x + 1, y), (x + 1, y + 1), (x + 1, y - 1)]
-                    .into_iter()
-                    .filter_map(|p| TryInto::<Position>::try_into(p).ok()),
-            );
+    #[must_use]
+    pub const fn exactly(symbol: char, parts: usize, combine: Combine) -> Self {
+        Self::new(symbol, parts..=parts, combine)
+    }
+
+    /// Whether a `symbol` with the `parts` around it is a gear
+    #[must_use]
+    pub fn qualifies(&self, symbol: &Symbol, parts: &[&PartNumber]) -> bool {
+        symbol.symbol == self.symbol && self.parts.contains(&parts.len())
+    }
+
+    /// The ratio of a `symbol` with the `parts` around it, `None` when it is
+    /// not a gear or the ratio overflows
+    #[must_use]
+    pub fn ratio(&self, symbol: &Symbol, parts: &[&PartNumber]) -> Option<u64> {
+        if !self.qualifies(symbol, parts) {
+            return None;
+        }
+
+        let mut ids = parts.iter().map(|part| part.id);
+
+        match self.combine {
+            Combine::Product => ids.try_fold(1, u64::checked_mul),
+            Combine::Sum => ids.try_fold(0, u64::checked_add),
+            Combine::Max => ids.max(),
         }
     }
+}
 
-    set
+/// A symbol with its adjacent parts, whether it qualifies as a gear and its
+/// ratio, `None` when it does not qualify or the ratio overflows
+#[derive(Debug)]
+pub struct SymbolReport<'a> {
+    pub symbol: &'a Symbol,
+    pub parts: Vec<&'a PartNumber>,
+    pub qualifies: bool,
+    pub ratio: Option<u64>,
 }
 
 /// Parses schematic for the gondola lift
@@ -149,39 +264,47 @@ pub fn parse_schematic(schematic: &str) -> Result<Schematic> {
     let mut parts: Vec<PartNumber> = vec![];
 
     for (y, line) in schematic.lines().enumerate() {
-        let mut current_number = None;
+        let mut current_number: Option<(usize, u64)> = None;
+        let mut length = 0;
 
         for (x, c) in line.chars().enumerate() {
-            if c.is_ascii_digit() {
-                let n = u64::from(c.to_digit(10).context("is ascii digit")?);
-                current_number.replace(add_next_ten(current_number, n));
+            length = x + 1;
+
+            if let Some(digit) = c.to_digit(10) {
+                let start = current_number.map_or(x, |(start, _)| start);
+                let n = add_next_ten(current_number.map(|(_, n)| n), u64::from(digit))
+                    .context("Part number is too big")?;
+                current_number.replace((start, n));
                 continue;
             }
 
-            if let Some(n) = current_number.take() {
-                parts.push(PartNumber::new(n, x, y));
-            };
+            if let Some((start, n)) = current_number.take() {
+                parts.push(PartNumber::new(n, start..x, y));
+            }
 
             if c != '.' {
                 symbols.push(Symbol::new(c, x, y));
             }
         }
 
-        if let Some(n) = current_number.take() {
-            parts.push(PartNumber::new(n, line.len(), y));
-        };
+        if let Some((start, n)) = current_number.take() {
+            parts.push(PartNumber::new(n, start..length, y));
+        }
     }
 
-    Ok(Schematic { parts, symbols })
+    Ok(Schematic::new(parts, symbols))
 }
 
 #[must_use]
-fn add_next_ten(n: Option<u64>, m: u64) -> u64 {
-    n.map_or(m, |n| n * 10 + m)
+fn add_next_ten(n: Option<u64>, m: u64) -> Option<u64> {
+    n.map_or(Some(m), |n| n.checked_mul(10)?.checked_add(m))
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
@@ -190,12 +313,267 @@ mod tests {
 
         let m = 6;
 
-        n.replace(add_next_ten(n, m));
+        n = add_next_ten(n, m);
 
         let m = 7;
 
         let result = add_next_ten(n, m);
 
-        assert_eq!(467, result);
+        assert_eq!(Some(467), result);
+    }
+
+    #[test]
+    fn test_add_next_ten_overflow() {
+        assert_eq!(None, add_next_ten(Some(u64::MAX), 1));
+    }
+
+    const SCHEMATIC: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(3, Position { x: 0, y: 0 }.neighbours().count());
+        assert_eq!(8, Position { x: 1, y: 1 }.neighbours().count());
+    }
+
+    #[test]
+    fn test_parts_adjacent_to() -> Result<()> {
+        let schematic = parse_schematic(SCHEMATIC)?;
+        let gear = schematic
+            .symbol_at((3, 1).into())
+            .context("Symbol at 3,1")?;
+
+        let ids = schematic
+            .parts_adjacent_to(gear)
+            .iter()
+            .map(|part| part.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![467, 35], ids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbols_adjacent_to() -> Result<()> {
+        let schematic = parse_schematic(SCHEMATIC)?;
+        let part = schematic.part_at((6, 2).into()).context("Part at 6,2")?;
+
+        let symbols = schematic
+            .symbols_adjacent_to(part)
+            .iter()
+            .map(|symbol| symbol.symbol)
+            .collect::<Vec<_>>();
+
+        assert_eq!(633, part.id);
+        assert_eq!(vec!['#'], symbols);
+
+        let lonely = schematic.part_at((5, 0).into()).context("Part at 5,0")?;
+
+        assert!(schematic.symbols_adjacent_to(lonely).is_empty());
+        assert!(!schematic.is_valid(lonely));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(GearRule::default(), 467_835)]
+    #[case(GearRule::exactly('*', 2, Combine::Sum), 467 + 35 + 755 + 598)]
+    #[case(GearRule::exactly('*', 2, Combine::Max), 467 + 755)]
+    #[case(GearRule::exactly('*', 1, Combine::Product), 617)]
+    #[case(GearRule::new('*', 1..=2, Combine::Sum), 467 + 35 + 617 + 755 + 598)]
+    #[case(GearRule::exactly('+', 1, Combine::Product), 592)]
+    #[case(GearRule::exactly('#', 2, Combine::Product), 0)]
+    fn test_gear_rules(#[case] rule: GearRule, #[case] expected: u64) -> Result<()> {
+        let schematic = parse_schematic(SCHEMATIC)?;
+
+        let result = schematic
+            .symbols()
+            .iter()
+            .filter_map(|symbol| schematic.gear_ratio(symbol, &rule))
+            .sum::<u64>();
+
+        assert_eq!(expected, result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ratio_overflow() {
+        let parts = [u64::MAX, 2].map(|id| PartNumber::new(id, 0..1, 0));
+        let parts = parts.iter().collect::<Vec<_>>();
+        let gear = Symbol::new('*', 1, 1);
+
+        assert!(GearRule::default().qualifies(&gear, &parts));
+        assert_eq!(None, GearRule::default().ratio(&gear, &parts));
+    }
+
+    #[test]
+    fn test_max_of_no_parts() {
+        let rule = GearRule::new('*', 0..=1, Combine::Max);
+        let gear = Symbol::new('*', 1, 1);
+
+        assert!(rule.qualifies(&gear, &[]));
+        assert_eq!(None, rule.ratio(&gear, &[]));
+    }
+
+    #[test]
+    fn test_symbol_reports() -> Result<()> {
+        let schematic = parse_schematic(SCHEMATIC)?;
+        let rule = GearRule::default();
+
+        let reports = schematic
+            .symbol_reports(&rule)
+            .map(|report| {
+                (
+                    report.symbol.symbol,
+                    report.parts.iter().map(|part| part.id).collect::<Vec<_>>(),
+                    report.qualifies,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                ('*', vec![467, 35], true),
+                ('#', vec![633], false),
+                ('*', vec![617], false),
+                ('+', vec![592], false),
+                ('$', vec![664], false),
+                ('*', vec![755, 598], true),
+            ],
+            reports
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_schematic() -> Result<()> {
+        let block = "11.22\n..*..\n33.44\n";
+        let input = block.repeat(20_000);
+
+        let schematic = parse_schematic(&input)?;
+
+        let valid = schematic
+            .parts()
+            .iter()
+            .filter(|part| schematic.is_valid(part))
+            .count();
+        let ratios = schematic
+            .symbols()
+            .iter()
+            .filter_map(|symbol| schematic.gear_ratio(symbol, &GearRule::default()))
+            .count();
+
+        assert_eq!(80_000, valid);
+        assert_eq!(0, ratios);
+
+        Ok(())
+    }
+
+    /// Generates a schematic grid, mostly empty with runs of digits and a few
+    /// symbols
+    fn grid() -> impl Strategy<Value = Vec<Vec<char>>> {
+        let cell = prop_oneof![
+            4 => Just('.'),
+            3 => prop::char::range('0', '9'),
+            1 => prop::sample::select(&['*', '#', '+', '$', '/', '@'][..]),
+        ];
+
+        grids::grid(cell, 1..12, 1..8)
+    }
+
+    /// The runs of digits of each row as `(id, xs, y)`
+    fn naive_parts(grid: &[Vec<char>]) -> Vec<(u64, std::ops::Range<usize>, usize)> {
+        let mut parts = Vec::new();
+
+        for (y, row) in grid.iter().enumerate() {
+            let mut x = 0;
+
+            while x < row.len() {
+                let length = row[x..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+                if length > 0 {
+                    let id = row[x..x + length].iter().collect::<String>();
+                    parts.push((id.parse().expect("Runs are digits"), x..x + length, y));
+                }
+
+                x += length.max(1);
+            }
+        }
+
+        parts
+    }
+
+    fn is_symbol(grid: &[Vec<char>], position: Position) -> bool {
+        grid.get(position.y)
+            .and_then(|row| row.get(position.x))
+            .is_some_and(|&c| c != '.' && !c.is_ascii_digit())
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_finds_every_run_of_digits(grid in grid()) {
+            let schematic = parse_schematic(&grids::text(&grid)).expect("Generated grid is valid");
+
+            let parts = schematic
+                .parts()
+                .iter()
+                .map(|part| {
+                    let xs = part.positions[0].x..part.positions[part.positions.len() - 1].x + 1;
+                    (part.id, xs, part.positions[0].y)
+                })
+                .collect::<Vec<_>>();
+
+            let symbols = grid.iter().flatten().filter(|&&c| c != '.' && !c.is_ascii_digit());
+
+            prop_assert_eq!(naive_parts(&grid), parts);
+            prop_assert_eq!(symbols.count(), schematic.symbols().len());
+        }
+
+        #[test]
+        fn test_adjacency_matches_naive_search(grid in grid()) {
+            let schematic = parse_schematic(&grids::text(&grid)).expect("Generated grid is valid");
+
+            for part in schematic.parts() {
+                let touches = part
+                    .positions
+                    .iter()
+                    .flat_map(|p| p.neighbours())
+                    .any(|p| is_symbol(&grid, p));
+
+                prop_assert_eq!(touches, schematic.is_valid(part));
+                prop_assert_eq!(touches, !schematic.symbols_adjacent_to(part).is_empty());
+            }
+
+            for symbol in schematic.symbols() {
+                let expected = naive_parts(&grid)
+                    .into_iter()
+                    .filter(|(_, xs, y)| {
+                        xs.clone()
+                            .flat_map(|x| Position::from((x, *y)).neighbours())
+                            .any(|p| p == symbol.position)
+                    })
+                    .map(|(_, xs, y)| Position::from((xs.start, y)))
+                    .collect::<Vec<_>>();
+
+                let adjacent = schematic
+                    .parts_adjacent_to(symbol)
+                    .into_iter()
+                    .map(|part| part.positions[0])
+                    .collect::<Vec<_>>();
+
+                prop_assert_eq!(expected, adjacent);
+            }
+        }
     }
 }
@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
 
 use anyhow::{Context, Result};
 
@@ -17,6 +20,163 @@ impl ScratchCard {
     }
 }
 
+/// How many of the following cards a winning card gives copies of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyRule {
+    /// One following card per matching number, the puzzle rule
+    Matches,
+    /// A fixed number of following cards for any card with a match
+    Next(usize),
+    /// As many following cards as the card's score: one for the first match,
+    /// doubled for every other one
+    DoublingScore,
+}
+
+impl CopyRule {
+    #[must_use]
+    pub fn copies(self, matches: usize) -> usize {
+        match (self, matches) {
+            (_, 0) => 0,
+            (Self::Matches, matches) => matches,
+            (Self::Next(n), _) => n,
+            (Self::DoublingScore, matches) => u32::try_from(matches - 1)
+                .ok()
+                .and_then(|shift| 1usize.checked_shl(shift))
+                .unwrap_or(usize::MAX),
+        }
+    }
+}
+
+/// How a card ended up in the table once the cascade reached it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardTally {
+    pub id: u32,
+    pub matches: usize,
+    /// The original card plus every copy won
+    pub instances: u64,
+    /// How many of the following cards each instance gives a copy of,
+    /// including cards past the end
+    pub gives: usize,
+}
+
+impl CardTally {
+    #[must_use]
+    pub const fn copies_won(&self) -> u64 {
+        self.instances - 1
+    }
+}
+
+/// Earlier cards that gave copies of the card at `index` of `tallies`, with
+/// how many each gave.
+///
+/// Each tally only records how far its copies reach, so the provenance of a
+/// card is worked out from the cards before it when asked for.
+pub fn won_from(tallies: &[CardTally], index: usize) -> impl Iterator<Item = (u32, u64)> + '_ {
+    tallies[..index]
+        .iter()
+        .enumerate()
+        .filter(move |(i, tally)| index - i <= tally.gives)
+        .map(|(_, tally)| (tally.id, tally.instances))
+}
+
+/// Plays the scratchcards in order, resolving the copies each card wins as
+/// cards arrive, so it works over [`parse_cards`] without collecting it.
+///
+/// Copies that would go to cards after the last one are not an error, they
+/// are counted by [`Cascade::past_end`] once the cards are exhausted. The
+/// cascade stops after the first card that fails to parse or overflows.
+pub struct Cascade<I> {
+    cards: I,
+    rule: CopyRule,
+    /// Position of the next card
+    position: u128,
+    /// Cards still giving copies, as the position of the last card they give
+    /// a copy of and their instances
+    pending: BinaryHeap<Reverse<(u128, u64)>>,
+    /// Instances of every pending card, the copies the next card wins
+    active: u128,
+    failed: bool,
+}
+
+impl<I> Cascade<I>
+where
+    I: Iterator<Item = Result<ScratchCard>>,
+{
+    pub fn new(cards: impl IntoIterator<IntoIter = I>, rule: CopyRule) -> Self {
+        Self {
+            cards: cards.into_iter(),
+            rule,
+            position: 0,
+            pending: BinaryHeap::new(),
+            active: 0,
+            failed: false,
+        }
+    }
+
+    /// Copies won for cards past the end of the cards seen so far
+    #[must_use]
+    pub fn past_end(&self) -> u128 {
+        self.pending
+            .iter()
+            .map(|Reverse((last, instances))| {
+                let remaining = (last + 1).saturating_sub(self.position);
+
+                u128::from(*instances).saturating_mul(remaining)
+            })
+            .fold(0, u128::saturating_add)
+    }
+
+    fn tally(&mut self, card: &ScratchCard) -> Result<CardTally> {
+        while let Some(Reverse((last, instances))) = self.pending.peek() {
+            if *last >= self.position {
+                break;
+            }
+
+            self.active -= u128::from(*instances);
+            self.pending.pop();
+        }
+
+        let instances = u64::try_from(self.active + 1)
+            .with_context(|| format!("Copies of card {} overflow", card.id))?;
+
+        let matches = card.winning_count();
+        let gives = self.rule.copies(matches);
+
+        if gives > 0 {
+            self.pending
+                .push(Reverse((self.position + gives as u128, instances)));
+            self.active += u128::from(instances);
+        }
+
+        self.position += 1;
+
+        Ok(CardTally {
+            id: card.id,
+            matches,
+            instances,
+            gives,
+        })
+    }
+}
+
+impl<I> Iterator for Cascade<I>
+where
+    I: Iterator<Item = Result<ScratchCard>>,
+{
+    type Item = Result<CardTally>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let tally = self.cards.next()?.and_then(|card| self.tally(&card));
+        self.failed = tally.is_err();
+
+        Some(tally)
+    }
+}
+
 /// Parses an of cards
 ///
 /// Example input:
@@ -58,3 +218,210 @@ fn parse_card(input: &str) -> Result<ScratchCard> {
 fn to_numbers(string: &str) -> Result<HashSet<u32>> {
     string.split_whitespace().map(|s| Ok(s.parse()?)).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rstest::rstest;
+
+    use super::*;
+
+    const CARDS: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[rstest]
+    #[case(CopyRule::Matches, 0, 0)]
+    #[case(CopyRule::Matches, 3, 3)]
+    #[case(CopyRule::Next(2), 0, 0)]
+    #[case(CopyRule::Next(2), 5, 2)]
+    #[case(CopyRule::DoublingScore, 1, 1)]
+    #[case(CopyRule::DoublingScore, 4, 8)]
+    #[case(CopyRule::DoublingScore, 200, usize::MAX)]
+    fn test_copy_rule(#[case] rule: CopyRule, #[case] matches: usize, #[case] expected: usize) {
+        assert_eq!(expected, rule.copies(matches));
+    }
+
+    #[test]
+    fn test_cascade_provenance() -> Result<()> {
+        let mut cascade = Cascade::new(parse_cards(CARDS), CopyRule::Matches);
+
+        let tallies = cascade.by_ref().collect::<Result<Vec<_>>>()?;
+
+        let instances = tallies.iter().map(|t| t.instances).collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 2, 4, 8, 14, 1], instances);
+        assert_eq!(
+            vec![(1, 1), (2, 2)],
+            won_from(&tallies, 2).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 1), (3, 4), (4, 8)],
+            won_from(&tallies, 4).collect::<Vec<_>>()
+        );
+        assert_eq!(None, won_from(&tallies, 5).next());
+        assert_eq!(0, cascade.past_end());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cascade_past_end() -> Result<()> {
+        let input = "Card 1: 1 2 | 1 2\nCard 2: 3 4 5 | 3 4 5";
+
+        let mut cascade = Cascade::new(parse_cards(input), CopyRule::Matches);
+
+        let instances = cascade
+            .by_ref()
+            .map(|t| Ok(t?.instances))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Card 1 wins a copy of card 2 and of a missing card 3, card 2 then
+        // wins three missing cards twice
+        assert_eq!(vec![1, 2], instances);
+        assert_eq!(1 + 2 * 3, cascade.past_end());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cascade_next_n() -> Result<()> {
+        let cascade = Cascade::new(parse_cards(CARDS), CopyRule::Next(1));
+
+        let instances = cascade
+            .map(|t| Ok(t?.instances))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(vec![1, 2, 3, 4, 5, 1], instances);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cascade_reports_errors() {
+        let input = "Card 1: 1 | 1\nCard 2: x | 1\nCard 3: 1 | 1";
+
+        let results = Cascade::new(parse_cards(input), CopyRule::Matches).collect::<Vec<_>>();
+
+        assert_eq!(2, results.len());
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_cascade_without_end() -> Result<()> {
+        let input = (1..=2000)
+            .map(|id| format!("Card {id}: 1 | 1"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut cascade = Cascade::new(parse_cards(&input), CopyRule::Next(usize::MAX));
+        let tallies = cascade.by_ref().take(60).collect::<Result<Vec<_>>>()?;
+
+        // NOTE: every card doubles the copies of all the cards after it
+        assert_eq!(1 << 59, tallies[59].instances);
+        assert_eq!(59, won_from(&tallies, 59).count());
+        assert!(cascade.nth(4).is_some_and(|tally| tally.is_err()));
+        assert!(cascade.next().is_none());
+
+        Ok(())
+    }
+
+    /// Generates the winning numbers and the numbers you have of each card,
+    /// from a small range so they often match
+    fn cards() -> impl Strategy<Value = Vec<(HashSet<u32>, HashSet<u32>)>> {
+        let numbers = |size| prop::collection::hash_set(1..30_u32, size);
+
+        prop::collection::vec((numbers(0..6), numbers(0..10)), 1..20)
+    }
+
+    fn cards_input(cards: &[(HashSet<u32>, HashSet<u32>)]) -> String {
+        let join = |numbers: &HashSet<u32>| {
+            numbers
+                .iter()
+                .map(|n| format!("{n:>2}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        cards
+            .iter()
+            .enumerate()
+            .map(|(i, (winning, have))| {
+                format!("Card {:>3}: {} | {}", i + 1, join(winning), join(have))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Plays the cards with a table of instances, also returning the copies
+    /// won past the last card
+    fn naive_cascade(matches: &[usize], rule: CopyRule) -> (Vec<u64>, u128) {
+        let mut instances = vec![1; matches.len()];
+        let mut past_end = 0;
+
+        for (i, &count) in matches.iter().enumerate() {
+            let (copies, won) = (rule.copies(count), instances[i]);
+
+            for j in i + 1..=i + copies {
+                match instances.get_mut(j) {
+                    Some(n) => *n += won,
+                    None => past_end += u128::from(won),
+                }
+            }
+        }
+
+        (instances, past_end)
+    }
+
+    fn rule() -> impl Strategy<Value = CopyRule> {
+        prop_oneof![
+            Just(CopyRule::Matches),
+            (0..4_usize).prop_map(CopyRule::Next),
+            Just(CopyRule::DoublingScore),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_cards(cards in cards()) {
+            let parsed = parse_cards(&cards_input(&cards))
+                .collect::<Result<Vec<_>>>()
+                .expect("Generated cards are valid");
+
+            for (id, (card, (winning, have))) in (1..).zip(parsed.iter().zip(&cards)) {
+                prop_assert_eq!(id, card.id);
+                prop_assert_eq!(winning, &card.winning_numbers);
+                prop_assert_eq!(have, &card.numbers_you_have);
+                prop_assert_eq!(winning.intersection(have).count(), card.winning_count());
+            }
+        }
+
+        #[test]
+        fn test_cascade_matches_naive_table(cards in cards(), rule in rule()) {
+            let input = cards_input(&cards);
+            let matches = cards
+                .iter()
+                .map(|(winning, have)| winning.intersection(have).count())
+                .collect::<Vec<_>>();
+
+            let mut cascade = Cascade::new(parse_cards(&input), rule);
+            let tallies = cascade
+                .by_ref()
+                .collect::<Result<Vec<_>>>()
+                .expect("Generated cards are valid");
+            let instances = tallies.iter().map(|tally| tally.instances).collect::<Vec<_>>();
+
+            for (index, tally) in tallies.iter().enumerate() {
+                let won = won_from(&tallies, index).map(|(_, copies)| copies).sum::<u64>();
+
+                prop_assert_eq!(tally.copies_won(), won);
+            }
+
+            prop_assert_eq!(naive_cascade(&matches, rule), (instances, cascade.past_end()));
+        }
+    }
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let mut sections = input.split("\n\n");
+
+    if let Some(seeds) = sections.next() {
+        let _ = day_05::parse_seeds(seeds);
+    }
+
+    sections.for_each(|map| drop(day_05::parse_map(map)));
+});
@@ -2,9 +2,15 @@ use anyhow::{Context, Ok, Result};
 use day_05::AlmanacMap;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -21,9 +27,12 @@ fn process(input: &str) -> Result<u64> {
     let maps = sections
         .map(day_05::parse_map)
         .collect::<Result<Vec<AlmanacMap>>>()?;
+    timing::parsed();
 
     for map in maps {
-        seeds.iter_mut().for_each(|s| *s = map.convert(*s));
+        for seed in &mut seeds {
+            *seed = map.convert(*seed);
+        }
     }
 
     let result = seeds.into_iter().min().context("Vector must have seeds")?;
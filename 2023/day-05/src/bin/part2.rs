@@ -3,12 +3,17 @@ use std::ops::Range;
 use day_05::AlmanacMap;
 
 use anyhow::{Context, Ok, Result};
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -25,18 +30,24 @@ fn process(input: &str) -> Result<u64> {
     let maps = sections
         .map(day_05::parse_map)
         .collect::<Result<Vec<AlmanacMap>>>()?;
+    timing::parsed();
 
     let ranges = seeds
         .chunks_exact(2)
         .map(|range| range[0]..range[0] + range[1])
+        .filter(|range| !range.is_empty())
         .collect::<Vec<Range<u64>>>();
 
-    // NOTE: Redundant clone allowed for more performance with rayon
-    #[allow(clippy::redundant_clone)]
-    let result = ranges
-        .into_par_iter()
-        .flat_map(|range| range.clone())
-        .map(|seed| maps.iter().fold(seed, |seed, map| map.convert(seed)))
+    let result = maps
+        .iter()
+        .fold(ranges, |ranges, map| {
+            ranges
+                .into_iter()
+                .flat_map(|range| map.convert_range(range))
+                .collect()
+        })
+        .iter()
+        .map(|range| range.start)
         .min()
         .context("Must exist minimum location")?;
 
@@ -21,6 +21,44 @@ impl AlmanacMap {
             c.destination_range.start + number - c.source_range.start
         })
     }
+
+    /// Converts a whole range of numbers at once, splitting it where it
+    /// crosses the boundaries of the converters
+    #[must_use]
+    pub fn convert_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let mut pending = vec![range];
+        let mut converted = Vec::new();
+
+        for converter in &self.converters {
+            let source = &converter.source_range;
+            let destination = &converter.destination_range;
+
+            pending = pending
+                .into_iter()
+                .flat_map(|range| {
+                    let start = range.start.max(source.start);
+                    let end = range.end.min(source.end);
+
+                    if start >= end {
+                        return vec![range];
+                    }
+
+                    converted.push(
+                        destination.start + start - source.start
+                            ..destination.start + end - source.start,
+                    );
+
+                    [range.start..start, end..range.end]
+                        .into_iter()
+                        .filter(|r| !r.is_empty())
+                        .collect()
+                })
+                .collect();
+        }
+
+        converted.extend(pending);
+        converted
+    }
 }
 
 #[derive(Debug)]
@@ -144,3 +182,81 @@ pub fn parse_map(input: &str) -> Result<AlmanacMap> {
 fn parse_numbers(input: &str) -> Result<Vec<u64>> {
     input.split(' ').map(|n| Ok(n.parse::<u64>()?)).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    /// Generates an almanac map whose source ranges do not overlap, as in the puzzle input
+    fn map_input() -> impl Strategy<Value = String> {
+        let converters = prop::collection::vec((0..1000_u64, 1..50_u64, 0..50_u64), 0..8);
+
+        (0..CATEGORIES.len() - 1, converters).prop_map(|(category, converters)| {
+            let mut source_start = 0;
+            let lines = converters
+                .into_iter()
+                .map(|(destination, length, gap)| {
+                    let line = format!("{destination} {} {length}", source_start + gap);
+                    source_start += gap + length;
+                    line
+                })
+                .collect_vec();
+
+            let title = format!(
+                "{}-to-{} map:",
+                CATEGORIES[category],
+                CATEGORIES[category + 1]
+            );
+
+            std::iter::once(title).chain(lines).join("\n")
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_seeds(seeds in prop::collection::vec(any::<u64>(), 1..20)) {
+            let input = format!("seeds: {}", seeds.iter().join(" "));
+
+            prop_assert_eq!(seeds, parse_seeds(&input).expect("Generated seeds are valid"));
+        }
+
+        #[test]
+        fn test_convert_outside_converters_is_identity(map in map_input(), offset in 0..100_u64) {
+            let map = parse_map(&map).expect("Generated map is valid");
+
+            let end = map.converters.iter().map(|c| c.source_range.end).max().unwrap_or(0);
+
+            prop_assert_eq!(end + offset, map.convert(end + offset));
+        }
+
+        #[test]
+        fn test_convert_range_matches_brute_force(
+            map in map_input(),
+            start in 0..1500_u64,
+            length in 0..200_u64,
+        ) {
+            let map = parse_map(&map).expect("Generated map is valid");
+            let range = start..start + length;
+
+            let expected = range.clone().map(|n| map.convert(n)).sorted().collect_vec();
+
+            let result = map.convert_range(range).into_iter().flatten().sorted().collect_vec();
+
+            prop_assert_eq!(expected, result);
+        }
+    }
+}
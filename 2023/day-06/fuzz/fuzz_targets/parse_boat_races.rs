@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day_06::parse_boat_races(input);
+    let _ = day_06::parse_long_boat_race(input);
+});
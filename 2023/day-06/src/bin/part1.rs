@@ -1,9 +1,15 @@
-use anyhow::{Ok, Result};
+use anyhow::{Context, Ok, Result};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,8 +19,13 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let races = day_06::parse_boat_races(input)?;
+    timing::parsed();
 
-    races.into_iter().map(|r| r.solve_equation()).product()
+    races
+        .into_iter()
+        .map(|r| r.ways_to_win())
+        .try_fold(1, u64::checked_mul)
+        .context("Product of the ways to win overflows")
 }
 
 #[cfg(test)]
@@ -26,12 +37,21 @@ mod tests {
     use rstest::*;
 
     #[test]
-    fn test_example() {
+    fn test_product_overflow() {
+        let input = "Time:      10000000 10000000 10000000\nDistance:  0 0 0";
+
+        assert!(process(input).is_err());
+    }
+
+    #[test]
+    fn test_example() -> Result<()> {
         let input = "Time:      7  15   30\nDistance:  9  41  200";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(288, result);
+
+        Ok(())
     }
 
     #[rstest]
@@ -39,7 +59,7 @@ mod tests {
     #[case((15, 40).into(), 8)]
     #[case((30, 200).into(), 9)]
     fn test_race(#[case] race: BoatRace, #[case] expected: u64) {
-        let result = race.solve_equation().unwrap();
+        let result = race.ways_to_win();
 
         assert_eq!(expected, result);
     }
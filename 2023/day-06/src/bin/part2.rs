@@ -1,9 +1,15 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,8 +19,9 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let long_race = day_06::parse_long_boat_race(input)?;
+    timing::parsed();
 
-    long_race.solve_equation()
+    Ok(long_race.ways_to_win())
 }
 
 #[cfg(test)]
@@ -22,11 +29,13 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "Time:      7  15   30\nDistance:  9  41  200";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(71503, result);
+
+        Ok(())
     }
 }
@@ -1,14 +1,16 @@
+use std::str::FromStr;
+
 use anyhow::{ensure, Context, Result};
 use itertools::Itertools;
 
 #[derive(Debug)]
 pub struct BoatRace {
-    pub allowed_time: u32,
-    pub best_distance: u32,
+    pub allowed_time: u64,
+    pub best_distance: u128,
 }
 
-impl From<(u32, u32)> for BoatRace {
-    fn from((allowed_time, best_distance): (u32, u32)) -> Self {
+impl From<(u64, u128)> for BoatRace {
+    fn from((allowed_time, best_distance): (u64, u128)) -> Self {
         Self {
             allowed_time,
             best_distance,
@@ -18,47 +20,72 @@ impl From<(u32, u32)> for BoatRace {
 
 impl BoatRace {
     #[must_use]
-    pub const fn new(allowed_time: u32, best_distance: u32) -> Self {
+    pub const fn new(allowed_time: u64, best_distance: u128) -> Self {
         Self {
             allowed_time,
             best_distance,
         }
     }
 
-    /// # Errors
+    /// Distance travelled when holding the button for `hold` milliseconds
+    #[must_use]
+    pub const fn distance(&self, hold: u64) -> u128 {
+        if hold > self.allowed_time {
+            return 0;
+        }
+
+        hold as u128 * (self.allowed_time - hold) as u128
+    }
+
+    /// The hold time with the longest distance, with an odd allowed time
+    /// holding one millisecond longer goes just as far
+    #[must_use]
+    pub const fn best_hold_time(&self) -> u64 {
+        self.allowed_time / 2
+    }
+
+    /// Number of hold times that beat the best distance
     ///
-    /// Returns error if the equation cannot be solved for real numbers
-    pub fn solve_equation(&self) -> Result<u64> {
-        let time = f64::from(self.allowed_time);
-        let distance = f64::from(self.best_distance);
+    /// The winning holds are the integers strictly between the roots of
+    /// `h^2 - t*h + d`, found with an integer square root of the
+    /// discriminant and then nudged onto the exact boundary.
+    #[must_use]
+    pub fn ways_to_win(&self) -> u64 {
+        let time = u128::from(self.allowed_time);
+        let wins = |hold: u64| self.distance(hold) > self.best_distance;
 
-        let (start, end) = quadratic_formula(1f64, -time, distance)?;
+        // NOTE: no hold goes beyond (time / 2)^2, so a best distance whose
+        // quadruple overflows can never be beaten
+        let Some(discriminant) = time
+            .checked_mul(time)
+            .zip(self.best_distance.checked_mul(4))
+            .and_then(|(square, quadruple)| square.checked_sub(quadruple))
+        else {
+            return 0;
+        };
 
-        #[allow(clippy::cast_possible_truncation)]
-        #[allow(clippy::cast_sign_loss)]
-        let (start, end) = (start.floor() as u64, end.ceil() as u64);
+        let root = discriminant.isqrt();
 
-        Ok(end - 1 - start)
-    }
-}
+        // NOTE: (time - root) / 2 <= time / 2, so it fits in an u64
+        let mut first = u64::try_from((time - root.min(time)) / 2).unwrap_or(u64::MAX);
 
-/// Calculates the quadratic formula for real numbers
-#[inline]
-fn quadratic_formula(a: f64, b: f64, c: f64) -> Result<(f64, f64)> {
-    // NOTE: a must not be 0
-    ensure!(a > f64::EPSILON);
+        while first > 0 && wins(first - 1) {
+            first -= 1;
+        }
 
-    let discriminant = b.mul_add(b, -4f64 * a * c);
+        while first <= self.best_hold_time() && !wins(first) {
+            first += 1;
+        }
 
-    // NOTE: discriminant must be positive
-    ensure!(discriminant >= 0f64);
+        if first > self.best_hold_time() {
+            return 0;
+        }
 
-    let discriminant_sqrt = discriminant.sqrt();
+        // NOTE: distances are symmetric around half the allowed time
+        let last = self.allowed_time - first;
 
-    Ok((
-        (-b - discriminant_sqrt) / (2f64 * a),
-        (-b + discriminant_sqrt) / (2f64 * a),
-    ))
+        last - first + 1
+    }
 }
 
 /// Parses the boat races
@@ -83,8 +110,14 @@ pub fn parse_boat_races(input: &str) -> Result<Vec<BoatRace>> {
 pub fn parse_long_boat_race(input: &str) -> Result<BoatRace> {
     let (times, distances) = parse_times_and_distances(input)?;
 
-    let time = times.process_results(|mut it| it.join(""))?.parse()?;
-    let distance = distances.process_results(|mut it| it.join(""))?.parse()?;
+    let time = times
+        .process_results(|mut it| it.join(""))?
+        .parse()
+        .context("Time is too long")?;
+    let distance = distances
+        .process_results(|mut it| it.join(""))?
+        .parse()
+        .context("Distance is too long")?;
 
     Ok((time, distance).into())
 }
@@ -93,8 +126,8 @@ pub fn parse_long_boat_race(input: &str) -> Result<BoatRace> {
 fn parse_times_and_distances(
     input: &str,
 ) -> Result<(
-    impl Iterator<Item = Result<u32>> + '_,
-    impl Iterator<Item = Result<u32>> + '_,
+    impl Iterator<Item = Result<u64>> + '_,
+    impl Iterator<Item = Result<u128>> + '_,
 )> {
     let (times, distances) = input
         .split_once('\n')
@@ -107,10 +140,14 @@ fn parse_times_and_distances(
 }
 
 #[inline]
-fn parse_line<'a>(
+fn parse_line<'a, T>(
     input: &'a str,
     expected_tag: &str,
-) -> Result<impl Iterator<Item = Result<u32>> + 'a> {
+) -> Result<impl Iterator<Item = Result<T>> + 'a>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
     let (tag, values) = input
         .split_once(':')
         .context("each line must have a : separating tag and content")?;
@@ -119,3 +156,154 @@ fn parse_line<'a>(
 
     Ok(values.split_whitespace().map(|n| Ok(n.parse()?)))
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn brute_force(race: &BoatRace) -> u64 {
+        let wins = (0..=race.allowed_time)
+            .filter(|&hold| race.distance(hold) > race.best_distance)
+            .count();
+
+        u64::try_from(wins).unwrap_or(u64::MAX)
+    }
+
+    #[rstest]
+    #[case(7, 9, 4)]
+    #[case(15, 40, 8)]
+    #[case(30, 200, 9)]
+    #[case(4, 3, 1)]
+    #[case(4, 4, 0)]
+    #[case(4, 5, 0)]
+    #[case(0, 0, 0)]
+    #[case(1, 0, 0)]
+    #[case(2, 0, 1)]
+    #[case(71_530, 940_200, 71_503)]
+    fn test_ways_to_win(#[case] time: u64, #[case] distance: u128, #[case] expected: u64) {
+        assert_eq!(expected, BoatRace::new(time, distance).ways_to_win());
+    }
+
+    #[test]
+    fn test_ways_to_win_matches_brute_force() {
+        for time in 0..80 {
+            for distance in 0..=u128::from(time * time / 4 + 2) {
+                let race = BoatRace::new(time, distance);
+
+                assert_eq!(brute_force(&race), race.ways_to_win(), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_ways_to_win_perfect_squares() {
+        // NOTE: distance k * (time - k) makes the discriminant a perfect square
+        // and k a root, which must not win
+        for time in [10, 1_000_001, 1 << 40, u64::MAX - 1] {
+            for k in [1, 2, time / 3, time / 2 - 1] {
+                let race = BoatRace::new(time, u128::from(k) * u128::from(time - k));
+
+                assert_eq!(time - 2 * k - 1, race.ways_to_win(), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_ways_to_win_beyond_f64() {
+        let time = u64::MAX;
+        let best = BoatRace::new(time, 0).distance(time / 2);
+
+        assert_eq!(0, BoatRace::new(time, best).ways_to_win());
+        assert_eq!(2, BoatRace::new(time, best - 1).ways_to_win());
+        assert_eq!(time - 1, BoatRace::new(time, 0).ways_to_win());
+    }
+
+    #[test]
+    fn test_ways_to_win_unbeatable_distance() -> Result<()> {
+        assert_eq!(0, BoatRace::new(u64::MAX, u128::MAX).ways_to_win());
+        assert_eq!(0, BoatRace::new(7, u128::MAX / 4 + 1).ways_to_win());
+
+        let race =
+            parse_long_boat_race("Time: 7\nDistance: 340282366920938463463374607431768211455")?;
+
+        assert_eq!(u128::MAX, race.best_distance);
+        assert_eq!(0, race.ways_to_win());
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(7, 3, 12)]
+    #[case(8, 4, 16)]
+    #[case(0, 0, 0)]
+    fn test_best_hold_time(#[case] time: u64, #[case] hold: u64, #[case] distance: u128) {
+        let race = BoatRace::new(time, 0);
+
+        assert_eq!(hold, race.best_hold_time());
+        assert_eq!(distance, race.distance(hold));
+        assert!((0..=time).all(|h| race.distance(h) <= distance));
+    }
+
+    #[test]
+    fn test_parse_long_race_beyond_u32() -> Result<()> {
+        let race = parse_long_boat_race("Time: 71 530\nDistance: 940 200 123 456 789")?;
+
+        assert_eq!(71_530, race.allowed_time);
+        assert_eq!(940_200_123_456_789, race.best_distance);
+
+        Ok(())
+    }
+
+    /// Generates a race that is close to the limit, so both winning and
+    /// losing races come up
+    fn race() -> impl Strategy<Value = BoatRace> {
+        (0..2_000_u64)
+            .prop_flat_map(|time| (Just(time), 0..=u128::from(time * time / 4 + 1)))
+            .prop_map(BoatRace::from)
+    }
+
+    fn races_input(races: &[BoatRace]) -> String {
+        let row = |values: Vec<String>| {
+            values
+                .iter()
+                .map(|value| format!("{value:>8}"))
+                .collect::<Vec<_>>()
+                .concat()
+        };
+
+        format!(
+            "Time:{}\nDistance:{}",
+            row(races.iter().map(|r| r.allowed_time.to_string()).collect()),
+            row(races.iter().map(|r| r.best_distance.to_string()).collect()),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_ways_to_win_matches_generated_brute_force(race in race()) {
+            prop_assert_eq!(brute_force(&race), race.ways_to_win());
+        }
+
+        #[test]
+        fn test_parse_generated_races(races in prop::collection::vec(race(), 1..4)) {
+            // NOTE: at most three races so the long race fits in an u64
+            let input = races_input(&races);
+
+            let parsed = parse_boat_races(&input).expect("Generated races are valid");
+            let long = parse_long_boat_race(&input).expect("Generated long race is valid");
+
+            let times = races.iter().map(|r| r.allowed_time.to_string()).collect::<String>();
+            let distances = races.iter().map(|r| r.best_distance.to_string()).collect::<String>();
+
+            prop_assert_eq!(
+                races.iter().map(|r| (r.allowed_time, r.best_distance)).collect::<Vec<_>>(),
+                parsed.iter().map(|r| (r.allowed_time, r.best_distance)).collect::<Vec<_>>()
+            );
+            prop_assert_eq!(times.parse::<u64>().ok(), Some(long.allowed_time));
+            prop_assert_eq!(distances.parse::<u128>().ok(), Some(long.best_distance));
+        }
+    }
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use day_07::Ruleset;
+
+fuzz_target!(|input: &str| {
+    let _ = day_07::parse_camel_cards(input, &Ruleset::standard());
+    let _ = day_07::parse_camel_cards(input, &Ruleset::jokers());
+});
@@ -1,66 +1,257 @@
-use std::cmp::Ordering;
-use std::collections::BTreeMap;
-use std::hash::Hash;
+use std::fmt::{self, Display};
 use std::str::FromStr;
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
+use itertools::Itertools;
 
-#[derive(Debug)]
-pub struct CamelHand<C: Card, T: HandType<C>> {
-    pub cards: [C; 5],
-    pub hand_type: T,
+/// The rules a game of camel cards is played with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    hand_size: usize,
+    ranking: Vec<char>,
+    wilds: Vec<char>,
+    wilds_lowest: bool,
 }
 
-pub trait HandType<C: Card>: Ord {
-    /// Returns the Hand type given a hand of cards
-    fn from_cards(hand: &[C; 5]) -> Self;
-}
+impl Ruleset {
+    /// Creates a ruleset without wilds, `ranking` lists the cards from
+    /// weakest to strongest
+    ///
+    /// # Errors
+    /// If the hand is empty, there are no cards, or a card is repeated
+    pub fn new(hand_size: usize, ranking: &str) -> Result<Self> {
+        ensure!(hand_size > 0, "Hands must have at least one card");
+        ensure!(!ranking.is_empty(), "Ranking must have at least one card");
+        ensure!(
+            ranking.chars().all_unique(),
+            "Ranking must not repeat cards"
+        );
+
+        Ok(Self {
+            hand_size,
+            ranking: ranking.chars().collect(),
+            wilds: Vec::new(),
+            wilds_lowest: false,
+        })
+    }
+
+    /// The rules of the first part, five cards from `2` to `A`
+    #[must_use]
+    pub fn standard() -> Self {
+        Self {
+            hand_size: 5,
+            ranking: "23456789TJQKA".chars().collect(),
+            wilds: Vec::new(),
+            wilds_lowest: false,
+        }
+    }
 
-pub trait Card: Ord + Hash + Sized {
-    /// Returns a card from a character
+    /// The rules of the second part, `J` is a joker ranked lowest
+    #[must_use]
+    pub fn jokers() -> Self {
+        Self {
+            wilds: vec!['J'],
+            wilds_lowest: true,
+            ..Self::standard()
+        }
+    }
+
+    /// Makes `wilds` stand in for whichever card gives the best hand
     ///
     /// # Errors
-    /// If the char does not represent a card
-    fn from_char(c: char) -> Result<Self>;
-}
+    /// If a wild is not in the ranking
+    pub fn with_wilds(mut self, wilds: &str) -> Result<Self> {
+        for wild in wilds.chars() {
+            ensure!(self.ranking.contains(&wild), "Wild {wild} is not a card");
+        }
 
-impl<C: Card, T: HandType<C>> FromStr for CamelHand<C, T> {
-    type Err = anyhow::Error;
+        self.wilds = wilds.chars().unique().collect();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cards: [C; 5] = s
+        Ok(self)
+    }
+
+    /// Makes wilds rank below every other card when breaking ties
+    #[must_use]
+    pub const fn with_wilds_lowest(mut self, wilds_lowest: bool) -> Self {
+        self.wilds_lowest = wilds_lowest;
+        self
+    }
+
+    #[must_use]
+    pub const fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    #[must_use]
+    pub fn is_wild(&self, card: char) -> bool {
+        self.wilds.contains(&card)
+    }
+
+    /// Strength of a card for breaking ties, higher is stronger
+    #[must_use]
+    pub fn strength(&self, card: char) -> Option<usize> {
+        let rank = self.ranking.iter().position(|&c| c == card)?;
+
+        if !self.wilds_lowest {
+            return Some(rank);
+        }
+
+        let wild = self.is_wild(card);
+        let below = self.ranking[..rank]
+            .iter()
+            .filter(|&&c| self.is_wild(c) == wild)
+            .count();
+
+        Some(if wild {
+            below
+        } else {
+            self.wilds.len() + below
+        })
+    }
+
+    /// Parses a hand of cards under this ruleset
+    ///
+    /// # Errors
+    /// If a card is unknown or the hand has the wrong size
+    pub fn hand(&self, cards: &str) -> Result<Hand> {
+        let hand = cards
             .chars()
-            .map(C::from_char)
-            .collect::<Result<Vec<C>>>()?
-            .try_into()
-            .ok()
-            .context("String must have exaclty 5 cards")?;
+            .map(|card| {
+                let strength = self
+                    .strength(card)
+                    .with_context(|| format!("Character {card} is not a card"))?;
+
+                Ok((card, strength))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        ensure!(
+            hand.len() == self.hand_size,
+            "Hand must have exactly {} cards",
+            self.hand_size
+        );
+
+        let wilds = hand.iter().filter(|(card, _)| self.is_wild(*card)).count();
+        let counts = hand
+            .iter()
+            .filter(|(card, _)| !self.is_wild(*card))
+            .counts_by(|(card, _)| card);
+        let kind = HandType::from_counts(counts.into_values(), wilds);
 
-        let hand_type = T::from_cards(&cards);
+        Ok(Hand {
+            kind,
+            strengths: hand.iter().map(|(_, strength)| *strength).collect(),
+            cards: hand.into_iter().map(|(card, _)| card).collect(),
+        })
+    }
+
+    /// Parses the camel cards under this ruleset
+    ///
+    /// # Errors
+    /// If the input is invalid
+    pub fn parse(&self, input: &str) -> Result<Vec<(Hand, u32)>> {
+        input
+            .lines()
+            .map(|line| {
+                let (hand, bid) = line
+                    .split_once(' ')
+                    .context("Hand and Bid must be space separated")?;
+
+                Ok((self.hand(hand)?, bid.parse::<u32>()?))
+            })
+            .collect()
+    }
+}
+
+/// The type of a hand, given by how many cards of each kind it holds with
+/// the wilds joining the largest group
+///
+/// Profiles compare lexicographically, which for five cards gives the usual
+/// order from high card up to five of a kind.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandType(Vec<usize>);
+
+impl HandType {
+    /// Builds the type from the counts of each non-wild card
+    pub fn from_counts(counts: impl IntoIterator<Item = usize>, wilds: usize) -> Self {
+        let mut profile = counts
+            .into_iter()
+            .filter(|&count| count > 0)
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .collect_vec();
+
+        match profile.first_mut() {
+            Some(largest) => *largest += wilds,
+            None if wilds > 0 => profile.push(wilds),
+            None => (),
+        }
+
+        Self(profile)
+    }
+
+    /// Group sizes from largest to smallest
+    #[must_use]
+    pub fn profile(&self) -> &[usize] {
+        &self.0
+    }
 
-        Ok(Self { cards, hand_type })
+    /// The common name of the type, if it has one
+    #[must_use]
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.0[..] {
+            [5] => "Five of a kind",
+            [4, 1] => "Four of a kind",
+            [3, 2] => "Full house",
+            [3, 1, 1] => "Three of a kind",
+            [2, 2, 1] => "Two pair",
+            [2, 1, 1, 1] => "One pair",
+            [1, 1, 1, 1, 1] => "High card",
+            _ => return None,
+        })
     }
 }
 
-impl<C: Card, T: HandType<C>> PartialEq for CamelHand<C, T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.cards == other.cards
+impl Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "{}", self.0.iter().join("-")),
+        }
     }
 }
 
-impl<C: Card, T: HandType<C>> Eq for CamelHand<C, T> {}
+/// A hand ordered by its type, then by the strength of its cards in order
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hand {
+    kind: HandType,
+    strengths: Vec<usize>,
+    cards: Vec<char>,
+}
+
+impl Hand {
+    #[must_use]
+    pub const fn hand_type(&self) -> &HandType {
+        &self.kind
+    }
 
-impl<C: Card, T: HandType<C>> Ord for CamelHand<C, T> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.hand_type
-            .cmp(&other.hand_type)
-            .then(self.cards.cmp(&other.cards))
+    #[must_use]
+    pub fn cards(&self) -> &[char] {
+        &self.cards
     }
 }
 
-impl<C: Card, T: HandType<C>> PartialOrd for CamelHand<C, T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cards.iter().collect::<String>())
+    }
+}
+
+impl FromStr for Hand {
+    type Err = anyhow::Error;
+
+    /// Parses a hand under the standard rules
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ruleset::standard().hand(s)
     }
 }
 
@@ -68,25 +259,266 @@ impl<C: Card, T: HandType<C>> PartialOrd for CamelHand<C, T> {
 ///
 /// # Errors
 /// If the input is invalid
-pub fn parse_camel_cards<C, T>(input: &str) -> Result<BTreeMap<CamelHand<C, T>, u32>>
-where
-    C: Card,
-    T: HandType<C>,
-{
-    input.lines().map(parse_camel_hand).collect()
+pub fn parse_camel_cards(input: &str, ruleset: &Ruleset) -> Result<Vec<(Hand, u32)>> {
+    ruleset.parse(input)
+}
+
+/// Sums every bid multiplied by the rank of its hand, equal hands taking
+/// consecutive ranks
+#[must_use]
+pub fn total_winnings(mut hands: Vec<(Hand, u32)>) -> u64 {
+    hands.sort_unstable();
+
+    hands
+        .into_iter()
+        .zip(1..)
+        .map(|((_, bid), rank)| rank * u64::from(bid))
+        .sum()
 }
 
-fn parse_camel_hand<C, T>(input: &str) -> Result<(CamelHand<C, T>, u32)>
-where
-    C: Card,
-    T: HandType<C>,
-{
-    let (hand, bid) = input
-        .split_once(' ')
-        .context("Hand and Bid must be space separated")?;
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rstest::rstest;
+
+    use super::*;
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[rstest]
+    #[case(Ruleset::standard(), 6440)]
+    #[case(Ruleset::jokers(), 5905)]
+    fn test_example(#[case] ruleset: Ruleset, #[case] expected: u64) -> Result<()> {
+        let result = total_winnings(ruleset.parse(EXAMPLE)?);
 
-    let hand = hand.parse::<CamelHand<C, T>>()?;
-    let bid = bid.parse::<u32>()?;
+        assert_eq!(expected, result);
 
-    Ok((hand, bid))
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("AAAAA", &[5])]
+    #[case("AA8AA", &[4, 1])]
+    #[case("23332", &[3, 2])]
+    #[case("TTT98", &[3, 1, 1])]
+    #[case("23432", &[2, 2, 1])]
+    #[case("A23A4", &[2, 1, 1, 1])]
+    #[case("23456", &[1, 1, 1, 1, 1])]
+    fn test_standard_profiles(#[case] cards: &str, #[case] profile: &[usize]) -> Result<()> {
+        assert_eq!(
+            profile,
+            Ruleset::standard().hand(cards)?.hand_type().profile()
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("JJJJJ", &[5])]
+    #[case("QJJQ2", &[4, 1])]
+    #[case("T55J5", &[4, 1])]
+    #[case("2345J", &[2, 1, 1, 1])]
+    #[case("2233J", &[3, 2])]
+    fn test_joker_profiles(#[case] cards: &str, #[case] profile: &[usize]) -> Result<()> {
+        assert_eq!(
+            profile,
+            Ruleset::jokers().hand(cards)?.hand_type().profile()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hand_type_order() -> Result<()> {
+        let ruleset = Ruleset::standard();
+        let names = [
+            "23456", "A23A4", "23432", "TTT98", "23332", "AA8AA", "AAAAA",
+        ]
+        .into_iter()
+        .map(|cards| Ok(ruleset.hand(cards)?.hand_type().clone()))
+        .collect::<Result<Vec<_>>>()?;
+
+        assert!(names.iter().tuple_windows().all(|(a, b)| a < b));
+        assert_eq!(
+            vec![
+                "High card",
+                "One pair",
+                "Two pair",
+                "Three of a kind",
+                "Full house",
+                "Four of a kind",
+                "Five of a kind",
+            ],
+            names.iter().map(ToString::to_string).collect_vec()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wilds_lowest() -> Result<()> {
+        let ruleset = Ruleset::jokers();
+
+        assert!(ruleset.hand("JKKK2")? < ruleset.hand("QQQQ2")?);
+        assert!(Ruleset::standard().hand("JKKK2")? > Ruleset::standard().hand("TKKK2")?);
+        assert_eq!(Some(0), ruleset.strength('J'));
+        assert_eq!(Some(1), ruleset.strength('2'));
+        assert_eq!(Some(12), ruleset.strength('A'));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_two_wildcards() -> Result<()> {
+        let ruleset = Ruleset::new(5, "*23456789TJQKA")?
+            .with_wilds("*J")?
+            .with_wilds_lowest(true);
+
+        assert_eq!(Some(0), ruleset.strength('*'));
+        assert_eq!(Some(1), ruleset.strength('J'));
+        assert_eq!(Some(2), ruleset.strength('2'));
+        assert_eq!(&[4, 1], ruleset.hand("*J2K2")?.hand_type().profile());
+        assert!(ruleset.hand("*KKK2")? < ruleset.hand("JKKK2")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_six_card_hands() -> Result<()> {
+        let ruleset = Ruleset::new(6, "23456789TJQKA")?.with_wilds("J")?;
+
+        let hand = ruleset.hand("KKJQQ2")?;
+
+        assert_eq!(&[3, 2, 1], hand.hand_type().profile());
+        assert_eq!("3-2-1", hand.hand_type().to_string());
+        assert!(ruleset.hand("2222AK")? > ruleset.hand("AAAKKK")?);
+        assert!(ruleset.hand("23456").is_err());
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(0, "23")]
+    #[case(5, "")]
+    #[case(5, "232")]
+    fn test_invalid_ruleset(#[case] hand_size: usize, #[case] ranking: &str) {
+        assert!(Ruleset::new(hand_size, ranking).is_err());
+    }
+
+    #[test]
+    fn test_invalid_hands() {
+        let ruleset = Ruleset::standard();
+
+        assert!(ruleset.hand("2345").is_err());
+        assert!(ruleset.hand("2345X").is_err());
+        assert!(ruleset.with_wilds("X").is_err());
+    }
+
+    #[test]
+    fn test_equal_hands_are_all_scored() -> Result<()> {
+        let hands = Ruleset::standard().parse("23456 1\n23456 2\n23457 10")?;
+
+        assert_eq!(1 + 2 * 2 + 3 * 10, total_winnings(hands));
+
+        Ok(())
+    }
+
+    const CARDS: &str = "23456789TJQKA";
+
+    type Game = Vec<(String, u32)>;
+
+    /// Generates five cards, drawn from a few kinds so groups are common
+    fn cards() -> impl Strategy<Value = String> {
+        prop::sample::subsequence(CARDS.chars().collect_vec(), 1..=5).prop_flat_map(|kinds| {
+            prop::collection::vec(prop::sample::select(kinds), 5)
+                .prop_map(|cards| cards.into_iter().collect())
+        })
+    }
+
+    /// Generates the lines of a game as hands and bids
+    fn game() -> impl Strategy<Value = Game> {
+        prop::collection::vec((cards(), 1..1000_u32), 1..30)
+    }
+
+    /// Generates a game along with the same lines in another order
+    fn shuffled_game() -> impl Strategy<Value = (Game, Game)> {
+        game().prop_flat_map(|game| (Just(game.clone()), Just(game).prop_shuffle()))
+    }
+
+    fn game_input(game: &[(String, u32)]) -> String {
+        game.iter()
+            .map(|(cards, bid)| format!("{cards} {bid}"))
+            .join("\n")
+    }
+
+    /// The best standard type out of every way to replace the jokers, with
+    /// the cards already in the hand or one that is not
+    fn naive_joker_type(cards: &str) -> HandType {
+        let mut candidates = cards.chars().filter(|&c| c != 'J').unique().collect_vec();
+        candidates.extend(CARDS.chars().find(|&c| c != 'J' && !cards.contains(c)));
+
+        cards
+            .chars()
+            .map(|c| {
+                if c == 'J' {
+                    candidates.clone()
+                } else {
+                    vec![c]
+                }
+            })
+            .multi_cartesian_product()
+            .map(|cards| {
+                let cards = cards.into_iter().collect::<String>();
+
+                Ruleset::standard()
+                    .hand(&cards)
+                    .expect("Replaced hand is valid")
+                    .kind
+            })
+            .max()
+            .expect("There is at least one replacement")
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_game(game in game()) {
+            let hands = parse_camel_cards(&game_input(&game), &Ruleset::standard())
+                .expect("Generated game is valid");
+
+            let parsed = hands.iter().map(|(hand, bid)| (hand.to_string(), *bid)).collect_vec();
+
+            prop_assert_eq!(game, parsed);
+        }
+
+        #[test]
+        fn test_standard_type_counts_cards(cards in cards()) {
+            let hand = Ruleset::standard().hand(&cards).expect("Generated hand is valid");
+
+            let profile = cards.chars().counts().into_values().sorted().rev().collect_vec();
+
+            prop_assert_eq!(profile, hand.hand_type().profile());
+        }
+
+        #[test]
+        fn test_jokers_make_the_best_type(cards in cards()) {
+            let hand = Ruleset::jokers().hand(&cards).expect("Generated hand is valid");
+
+            prop_assert_eq!(&naive_joker_type(&cards), hand.hand_type());
+        }
+
+        #[test]
+        fn test_winnings_ignore_order((game, shuffled) in shuffled_game()) {
+            let winnings = |game: &[(String, u32)]| {
+                parse_camel_cards(&game_input(game), &Ruleset::jokers())
+                    .map(total_winnings)
+                    .expect("Generated game is valid")
+            };
+
+            prop_assert_eq!(winnings(&game), winnings(&shuffled));
+        }
+    }
 }
@@ -1,9 +1,15 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,8 +19,9 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let (directions, network) = day_08::parser::parse_maps(input)?;
+    timing::parsed();
 
-    let result = day_08::get_steps_to_end(&directions, &network, "AAA", |s| s == "ZZZ");
+    let result = network.steps_to_end(&directions, "AAA", |s| s == "ZZZ")?;
 
     Ok(result)
 }
@@ -24,7 +31,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "RL
 
 AAA = (BBB, CCC)
@@ -35,21 +42,25 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(2, result);
+
+        Ok(())
     }
 
     #[test]
-    fn test_cycle() {
+    fn test_cycle() -> Result<()> {
         let input = "LLR
 
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(6, result);
+
+        Ok(())
     }
 }
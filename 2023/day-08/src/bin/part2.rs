@@ -1,9 +1,16 @@
 use anyhow::{Context, Result};
+use itertools::Itertools;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,12 +20,18 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let (directions, network) = day_08::parser::parse_maps(input)?;
+    timing::parsed();
 
     let result = network
-        .keys()
+        .nodes()
         .filter(|s| s.ends_with('A'))
-        .map(|n| day_08::get_steps_to_end(&directions, &network, n, |s| s.ends_with('Z')))
-        .reduce(num::integer::lcm)
+        .map(|n| {
+            network
+                .orbit(&directions, n, |s| s.ends_with('Z'))?
+                .lcm_period()
+                .with_context(|| format!("Walk from {n} does not end once per cycle"))
+        })
+        .process_results(|periods| periods.reduce(num::integer::lcm))?
         .context("There should be at least 1 starting node")?;
 
     Ok(result)
@@ -29,7 +42,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "LR
 
 11A = (11B, XXX)
@@ -41,8 +54,10 @@ mod tests {
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(6, result);
+
+        Ok(())
     }
 }
@@ -1,58 +1,259 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
 
-use itertools::FoldWhile::{Continue, Done};
+use anyhow::{ensure, Context, Result};
 use itertools::Itertools;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     Left,
     Right,
 }
 
-type Network<'a> = HashMap<&'a str, (&'a str, &'a str)>;
-
-/// Gets the steps to the end of the map following directions
-///
-/// # Panics
-/// Should not panic if the Network was properly created
-pub fn get_steps_to_end<F>(
-    directions: &[Direction],
-    network: &Network,
-    starting_node: &str,
-    ending_condition: F,
-) -> u64
-where
-    F: Fn(&str) -> bool,
-{
-    directions
-        .iter()
-        .cycle()
-        .fold_while((0, starting_node), |(count, node), direction| {
+/// A network of nodes where every node leads to two nodes that exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network<'a> {
+    nodes: HashMap<&'a str, (&'a str, &'a str)>,
+}
+
+impl<'a> Network<'a> {
+    /// Creates the network from each node and its left and right nodes
+    ///
+    /// # Errors
+    /// If a node leads to a node that is not in the network
+    pub fn new(nodes: HashMap<&'a str, (&'a str, &'a str)>) -> Result<Self> {
+        let missing = nodes
+            .iter()
+            .flat_map(|(node, (left, right))| [(node, left), (node, right)])
+            .filter(|(_, next)| !nodes.contains_key(*next))
+            .map(|(node, next)| format!("{node} -> {next}"))
+            .sorted()
+            .collect_vec();
+
+        ensure!(
+            missing.is_empty(),
+            "Network has edges to missing nodes: {}",
+            missing.join(", ")
+        );
+
+        Ok(Self { nodes })
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    #[must_use]
+    pub fn contains(&self, node: &str) -> bool {
+        self.nodes.contains_key(node)
+    }
+
+    /// Gets the node reached from `node` in a direction
+    #[must_use]
+    pub fn next(&self, node: &str, direction: Direction) -> Option<&'a str> {
+        let (left, right) = self.nodes.get(node)?;
+
+        Some(match direction {
+            Direction::Left => left,
+            Direction::Right => right,
+        })
+    }
+
+    /// Walks the network following the directions forever, starting with
+    /// the starting node itself
+    ///
+    /// # Errors
+    /// If the starting node does not exist or there are no directions
+    pub fn walk<'n>(
+        &'n self,
+        directions: &'n [Direction],
+        starting_node: &str,
+    ) -> Result<impl Iterator<Item = &'a str> + 'n> {
+        ensure!(
+            !directions.is_empty(),
+            "There must be at least one direction"
+        );
+
+        let (&start, _) = self
+            .nodes
+            .get_key_value(starting_node)
+            .with_context(|| format!("Starting node {starting_node} does not exist"))?;
+
+        // NOTE: nodes are validated on creation so there is always a next one
+        let walk = directions.iter().cycle().scan(start, |node, &direction| {
+            let current = *node;
+            *node = self.next(current, direction).unwrap_or(current);
+
+            Some(current)
+        });
+
+        Ok(walk)
+    }
+
+    /// Gets the steps to the first ending node following directions
+    ///
+    /// # Errors
+    /// If the starting node does not exist or no ending node can be reached
+    pub fn steps_to_end<F>(
+        &self,
+        directions: &[Direction],
+        starting_node: &str,
+        ending_condition: F,
+    ) -> Result<u64>
+    where
+        F: Fn(&str) -> bool,
+    {
+        // NOTE: after visiting every (node, direction) pair the walk repeats
+        let states = self.nodes.len() * directions.len();
+
+        let steps = self
+            .walk(directions, starting_node)?
+            .take(states + 1)
+            .position(ending_condition)
+            .with_context(|| format!("No ending node can be reached from {starting_node}"))?;
+
+        Ok(steps as u64)
+    }
+
+    /// Finds the tail and cycle of the walk from a starting node and where the
+    /// ending nodes fall in them
+    ///
+    /// # Errors
+    /// If the starting node does not exist or there are no directions
+    pub fn orbit<F>(
+        &self,
+        directions: &[Direction],
+        starting_node: &str,
+        ending_condition: F,
+    ) -> Result<Orbit>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut seen = HashMap::new();
+        let mut ends = Vec::new();
+
+        for (step, node) in self.walk(directions, starting_node)?.enumerate() {
+            let state = (node, step % directions.len());
+
+            if let Some(&tail) = seen.get(&state) {
+                let (ends_in_tail, ends_in_cycle): (Vec<_>, Vec<_>) =
+                    ends.into_iter().partition(|&end| end < tail);
+
+                return Ok(Orbit {
+                    tail: tail as u64,
+                    cycle: (step - tail) as u64,
+                    ends_in_tail: ends_in_tail.into_iter().map(|end| end as u64).collect(),
+                    ends_in_cycle: ends_in_cycle
+                        .into_iter()
+                        .map(|end| (end - tail) as u64)
+                        .collect(),
+                });
+            }
+
             if ending_condition(node) {
-                return Done((count, node));
+                ends.push(step);
             }
 
-            let current_node = network.get(node).expect("Node should exist");
+            seen.insert(state, step);
+        }
+
+        unreachable!("Walking the network never ends")
+    }
 
-            let next = match direction {
-                Direction::Left => current_node.0,
-                Direction::Right => current_node.1,
+    /// Exports the network in Graphviz DOT format, with starting nodes drawn
+    /// as boxes and ending nodes as double circles
+    pub fn to_dot<S, E>(&self, is_start: S, is_end: E) -> String
+    where
+        S: Fn(&str) -> bool,
+        E: Fn(&str) -> bool,
+    {
+        let nodes: BTreeMap<_, _> = self.nodes.iter().collect();
+        let mut dot = String::from("digraph network {\n");
+
+        for node in nodes.keys() {
+            let shape = match (is_start(node), is_end(node)) {
+                (true, _) => "box",
+                (false, true) => "doublecircle",
+                (false, false) => "circle",
             };
 
-            Continue((count + 1, next))
-        })
-        .into_inner()
-        .0
+            let _ = writeln!(dot, "    \"{node}\" [shape={shape}];");
+        }
+
+        for (node, (left, right)) in nodes {
+            if left == right {
+                let _ = writeln!(dot, "    \"{node}\" -> \"{left}\" [label=\"LR\"];");
+            } else {
+                let _ = writeln!(dot, "    \"{node}\" -> \"{left}\" [label=\"L\"];");
+                let _ = writeln!(dot, "    \"{node}\" -> \"{right}\" [label=\"R\"];");
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The shape of a walk through the network, a tail of steps that are never
+/// repeated followed by a cycle that repeats forever
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orbit {
+    pub tail: u64,
+    pub cycle: u64,
+    /// Steps in the tail on an ending node
+    pub ends_in_tail: Vec<u64>,
+    /// Offsets from the start of the cycle on an ending node
+    pub ends_in_cycle: Vec<u64>,
+}
+
+impl Orbit {
+    /// Whether the walk is on an ending node after some steps
+    #[must_use]
+    pub fn is_end(&self, step: u64) -> bool {
+        if step < self.tail {
+            return self.ends_in_tail.contains(&step);
+        }
+
+        self.ends_in_cycle
+            .contains(&((step - self.tail) % self.cycle))
+    }
+
+    /// Steps to the first ending node
+    #[must_use]
+    pub fn first_end(&self) -> Option<u64> {
+        self.ends_in_tail
+            .first()
+            .copied()
+            .or_else(|| Some(self.tail + self.ends_in_cycle.first()?))
+    }
+
+    /// The period when the walk is on an ending node exactly at its
+    /// multiples, which is when the LCM of every period is the first time
+    /// all walks end together
+    #[must_use]
+    pub fn lcm_period(&self) -> Option<u64> {
+        let period = self.first_end().filter(|&period| period > 0)?;
+
+        // NOTE: past the tail the walk repeats every cycle, so a window of
+        // one cycle after the tail covers every step, the step ending it
+        // standing for the start when there is no tail
+        let periodic = self.cycle.is_multiple_of(period)
+            && (1..=self.tail + self.cycle)
+                .all(|step| self.is_end(step) == step.is_multiple_of(period));
+
+        periodic.then_some(period)
+    }
 }
 
 pub mod parser {
     use super::{Direction, Network};
 
     use anyhow::{format_err, Result};
+    use std::collections::HashMap;
     use winnow::{
         ascii::{alphanumeric1, line_ending, multispace1},
         combinator::{
-            alt, delimited, dispatch, eof, fail, repeat, separated_pair, success, terminated,
+            alt, delimited, dispatch, empty, eof, fail, repeat, separated_pair, terminated,
         },
         token::any,
         PResult, Parser,
@@ -62,25 +263,27 @@ pub mod parser {
     ///
     /// # Errors
     /// Errors if the input is not valid
-    pub fn parse_maps(input: &str) -> Result<(Vec<Direction>, Network)> {
-        separated_pair(directions, multispace1, network)
+    pub fn parse_maps(input: &str) -> Result<(Vec<Direction>, Network<'_>)> {
+        let (directions, nodes) = separated_pair(directions, multispace1, network)
             .parse(input)
-            .map_err(|e| format_err!(e.to_string()))
+            .map_err(|e| format_err!(e.to_string()))?;
+
+        Ok((directions, Network::new(nodes)?))
     }
 
     fn directions(input: &mut &str) -> PResult<Vec<Direction>> {
         repeat(
             1..,
             dispatch!(any;
-                'L' => success(Direction::Left),
-                'R' => success(Direction::Right),
+                'L' => empty.value(Direction::Left),
+                'R' => empty.value(Direction::Right),
                 _ => fail,
             ),
         )
         .parse_next(input)
     }
 
-    fn network<'a>(input: &mut &'a str) -> PResult<Network<'a>> {
+    fn network<'a>(input: &mut &'a str) -> PResult<HashMap<&'a str, (&'a str, &'a str)>> {
         repeat(
             1..,
             terminated(
@@ -95,3 +298,254 @@ pub mod parser {
         .parse_next(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const GHOSTS: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    fn is_end(node: &str) -> bool {
+        node.ends_with('Z')
+    }
+
+    #[test]
+    fn test_missing_nodes() {
+        let result = parser::parse_maps("L\n\nAAA = (BBB, CCC)\nBBB = (AAA, AAA)");
+
+        let error = result.err().map(|e| e.to_string()).unwrap_or_default();
+
+        assert_eq!("Network has edges to missing nodes: AAA -> CCC", error);
+    }
+
+    #[test]
+    fn test_missing_start() -> Result<()> {
+        let (directions, network) = parser::parse_maps(GHOSTS)?;
+
+        assert!(network.steps_to_end(&directions, "33A", is_end).is_err());
+        assert!(network.orbit(&directions, "33A", is_end).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreachable_end() -> Result<()> {
+        let (directions, network) = parser::parse_maps(GHOSTS)?;
+
+        assert!(network.steps_to_end(&directions, "XXX", is_end).is_err());
+
+        let orbit = network.orbit(&directions, "XXX", is_end)?;
+
+        assert_eq!(None, orbit.first_end());
+        assert_eq!(None, orbit.lcm_period());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_orbits() -> Result<()> {
+        let (directions, network) = parser::parse_maps(GHOSTS)?;
+
+        let first = network.orbit(&directions, "11A", is_end)?;
+        let second = network.orbit(&directions, "22A", is_end)?;
+
+        assert_eq!(
+            Orbit {
+                tail: 1,
+                cycle: 2,
+                ends_in_tail: vec![],
+                ends_in_cycle: vec![1],
+            },
+            first
+        );
+        assert_eq!(
+            Orbit {
+                tail: 1,
+                cycle: 6,
+                ends_in_tail: vec![],
+                ends_in_cycle: vec![2, 5],
+            },
+            second
+        );
+        assert_eq!(Some(2), first.lcm_period());
+        assert_eq!(Some(3), second.lcm_period());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_orbit_agrees_with_walk() -> Result<()> {
+        let (directions, network) = parser::parse_maps(GHOSTS)?;
+
+        for start in ["11A", "22A", "XXX"] {
+            let orbit = network.orbit(&directions, start, is_end)?;
+
+            for (step, node) in network.walk(&directions, start)?.take(50).enumerate() {
+                assert_eq!(is_end(node), orbit.is_end(step as u64), "{start} {step}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_end_in_tail_breaks_lcm() -> Result<()> {
+        let (directions, network) = parser::parse_maps(
+            "L
+
+AAA = (11Z, 11Z)
+11Z = (BBB, BBB)
+BBB = (CCC, CCC)
+CCC = (BBB, BBB)",
+        )?;
+
+        let orbit = network.orbit(&directions, "AAA", is_end)?;
+
+        assert_eq!(vec![1], orbit.ends_in_tail);
+        assert_eq!(2, orbit.tail);
+        assert_eq!(2, orbit.cycle);
+        assert_eq!(Some(1), orbit.first_end());
+        assert_eq!(None, orbit.lcm_period());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_start_in_cycle_breaks_lcm() -> Result<()> {
+        let (directions, network) = parser::parse_maps("L\n\n00A = (01Z, 00A)\n01Z = (00A, 00A)")?;
+
+        let orbit = network.orbit(&directions, "00A", is_end)?;
+
+        assert_eq!(0, orbit.tail);
+        assert_eq!(2, orbit.cycle);
+        assert_eq!(Some(1), orbit.first_end());
+        assert!(!orbit.is_end(2));
+        assert_eq!(None, orbit.lcm_period());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_dot() -> Result<()> {
+        let (_, network) = parser::parse_maps("L\n\nAAA = (ZZZ, AAA)\nZZZ = (ZZZ, ZZZ)")?;
+
+        let dot = network.to_dot(|node| node.ends_with('A'), is_end);
+
+        assert_eq!(
+            r#"digraph network {
+    "AAA" [shape=box];
+    "ZZZ" [shape=doublecircle];
+    "AAA" -> "ZZZ" [label="L"];
+    "AAA" -> "AAA" [label="R"];
+    "ZZZ" -> "ZZZ" [label="LR"];
+}
+"#,
+            dot
+        );
+
+        Ok(())
+    }
+
+    /// A generated map: the directions and, for each node, whether it is an
+    /// ending node and the indices of its left and right nodes
+    type Map = (Vec<Direction>, Vec<(bool, usize, usize)>);
+
+    fn map() -> impl Strategy<Value = Map> {
+        let direction = prop_oneof![Just(Direction::Left), Just(Direction::Right)];
+
+        (prop::collection::vec(direction, 1..8), 1..12_usize).prop_flat_map(
+            |(directions, nodes)| {
+                let node = (prop::bool::weighted(0.3), 0..nodes, 0..nodes);
+
+                (Just(directions), prop::collection::vec(node, nodes))
+            },
+        )
+    }
+
+    fn name(nodes: &[(bool, usize, usize)], i: usize) -> String {
+        format!("{i:02}{}", if nodes[i].0 { 'Z' } else { 'A' })
+    }
+
+    fn map_input((directions, nodes): &Map) -> String {
+        let directions = directions
+            .iter()
+            .map(|direction| match direction {
+                Direction::Left => 'L',
+                Direction::Right => 'R',
+            })
+            .collect::<String>();
+
+        let nodes = (0..nodes.len())
+            .map(|i| {
+                let (_, left, right) = nodes[i];
+                format!(
+                    "{} = ({}, {})",
+                    name(nodes, i),
+                    name(nodes, left),
+                    name(nodes, right)
+                )
+            })
+            .join("\n");
+
+        format!("{directions}\n\n{nodes}")
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_map(map in map()) {
+            let input = map_input(&map);
+            let (directions, network) = parser::parse_maps(&input).expect("Generated map is valid");
+
+            prop_assert_eq!(&map.0, &directions);
+
+            for (i, &(_, left, right)) in map.1.iter().enumerate() {
+                let node = name(&map.1, i);
+                let next = |direction| network.next(&node, direction).map(String::from);
+
+                prop_assert_eq!(Some(name(&map.1, left)), next(Direction::Left));
+                prop_assert_eq!(Some(name(&map.1, right)), next(Direction::Right));
+            }
+        }
+
+        #[test]
+        fn test_orbit_matches_generated_walk(map in map()) {
+            let input = map_input(&map);
+            let (directions, network) = parser::parse_maps(&input).expect("Generated map is valid");
+            let start = name(&map.1, 0);
+
+            let orbit = network.orbit(&directions, &start, is_end).expect("Start exists");
+            let steps = 2 * map.0.len() * map.1.len() + 1;
+            let walk = network
+                .walk(&directions, &start)
+                .expect("Start exists")
+                .take(steps)
+                .collect_vec();
+
+            for (step, node) in (0..).zip(&walk) {
+                prop_assert_eq!(is_end(node), orbit.is_end(step));
+            }
+
+            let first = walk.iter().position(|node| is_end(node)).map(|step| step as u64);
+
+            prop_assert_eq!(first, orbit.first_end());
+            prop_assert_eq!(first, network.steps_to_end(&directions, &start, is_end).ok());
+
+            if let Some(period) = orbit.lcm_period() {
+                for (step, node) in (0..).zip(&walk).skip(1) {
+                    prop_assert_eq!(step % period == 0, is_end(node));
+                }
+            }
+        }
+    }
+}
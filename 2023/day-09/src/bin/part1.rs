@@ -1,10 +1,16 @@
 use anyhow::Result;
-use itertools::Itertools;
+use day_09::Polynomial;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -12,19 +18,11 @@ fn main() -> Result<()> {
 }
 
 #[inline]
-fn process(input: &str) -> Result<i32> {
+fn process(input: &str) -> Result<i128> {
     let data = day_09::parse_oasis_report(input);
 
-    let result = data.process_results(|it| {
-        it.map(|v| {
-            day_09::differences(v)
-                .map(|v| *v.last().expect("At least an element each iteration"))
-                .sum::<i32>()
-        })
+    data.map(|history| Polynomial::fit(&history?)?.forwards(1))
         .sum()
-    })?;
-
-    Ok(result)
 }
 
 #[cfg(test)]
@@ -32,11 +30,13 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(114, result);
+
+        Ok(())
     }
 }
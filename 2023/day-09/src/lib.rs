@@ -1,21 +1,293 @@
-use std::ops::Not;
-
-use anyhow::Result;
+use anyhow::{ensure, Context, Result};
 use itertools::Itertools;
 
-pub fn parse_oasis_report(input: &str) -> impl Iterator<Item = Result<Vec<i32>>> + '_ {
+pub fn parse_oasis_report(input: &str) -> impl Iterator<Item = Result<Vec<i64>>> + '_ {
     input
         .lines()
         .map(|l| l.split_whitespace().map(|n| Ok(n.parse()?)).collect())
 }
 
-pub fn differences(vec: Vec<i32>) -> impl Iterator<Item = Vec<i32>> {
-    std::iter::successors(Some(vec), |vec| {
-        vec.iter().all(|n| *n == 0).not().then_some(
-            vec.iter()
+/// The polynomial through a history, in Newton form over the forward
+/// differences at the first index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    coefficients: Vec<i128>,
+    samples: usize,
+}
+
+impl Polynomial {
+    /// Fits the polynomial through the values at indices `0..values.len()`
+    ///
+    /// A single value is a constant.
+    ///
+    /// # Errors
+    /// If there are no values or the differences of more than one value never
+    /// become all zero, meaning the values do not pin down a polynomial
+    pub fn fit(values: &[i64]) -> Result<Self> {
+        ensure!(!values.is_empty(), "History must have at least one value");
+
+        let mut row = values.iter().copied().map(i128::from).collect_vec();
+        let mut coefficients = Vec::new();
+
+        while row.iter().any(|&n| n != 0) {
+            coefficients.push(row[0]);
+
+            row = row
+                .iter()
+                .tuple_windows()
+                .map(|(a, b)| b.checked_sub(*a).context("Differences are too large"))
+                .collect::<Result<_>>()?;
+
+            ensure!(
+                !row.is_empty() || values.len() == 1,
+                "Differences of {values:?} never become all zero, it is not a polynomial"
+            );
+        }
+
+        Ok(Self {
+            coefficients,
+            samples: values.len(),
+        })
+    }
+
+    /// Degree of the polynomial, the zero polynomial has degree zero
+    #[must_use]
+    pub const fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// Forward differences at the first index, `c[j]` multiplies `C(n, j)`
+    #[must_use]
+    pub fn coefficients(&self) -> &[i128] {
+        &self.coefficients
+    }
+
+    /// Value at any index, negative ones are before the first value
+    ///
+    /// # Errors
+    /// If the value does not fit in an `i128`
+    pub fn at(&self, index: i64) -> Result<i128> {
+        let index = i128::from(index);
+        let mut binomial = 1_i128;
+        let mut value = 0_i128;
+
+        for (j, coefficient) in (0_i128..).zip(&self.coefficients) {
+            if j > 0 {
+                // NOTE: C(n, j) = C(n, j - 1) * (n - j + 1) / j is always exact
+                binomial = binomial
+                    .checked_mul(index - j + 1)
+                    .context("Value is too large")?
+                    / j;
+            }
+
+            value = coefficient
+                .checked_mul(binomial)
+                .and_then(|term| value.checked_add(term))
+                .context("Value is too large")?;
+        }
+
+        Ok(value)
+    }
+
+    /// Value `steps` after the last one
+    ///
+    /// # Errors
+    /// If the value does not fit in an `i128`
+    pub fn forwards(&self, steps: i64) -> Result<i128> {
+        let last = i64::try_from(self.samples)? - 1;
+
+        self.at(last.checked_add(steps).context("Index is too large")?)
+    }
+
+    /// Value `steps` before the first one
+    ///
+    /// # Errors
+    /// If the value does not fit in an `i128`
+    pub fn backwards(&self, steps: i64) -> Result<i128> {
+        self.at(steps.checked_neg().context("Index is too large")?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_example() -> Result<()> {
+        let cases = [
+            (vec![0, 3, 6, 9, 12, 15], 1, 18, -3),
+            (vec![1, 3, 6, 10, 15, 21], 2, 28, 0),
+            (vec![10, 13, 16, 21, 30, 45], 3, 68, 5),
+        ];
+
+        for (values, degree, next, previous) in cases {
+            let polynomial = Polynomial::fit(&values)?;
+
+            assert_eq!(degree, polynomial.degree());
+            assert_eq!(next, polynomial.forwards(1)?);
+            assert_eq!(previous, polynomial.backwards(1)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reproduces_values() -> Result<()> {
+        let values = [-7, 2, 31, 92, 197, 358, 587];
+        let polynomial = Polynomial::fit(&values)?;
+
+        for (index, value) in (0..).zip(values) {
+            assert_eq!(i128::from(value), polynomial.at(index)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_far_indices() -> Result<()> {
+        // NOTE: 2n^3 - n + 5
+        let f = |n: i128| 2 * n.pow(3) - n + 5;
+        let values = (0..6)
+            .map(|n| i64::try_from(f(n)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let polynomial = Polynomial::fit(&values)?;
+
+        assert_eq!(3, polynomial.degree());
+        assert_eq!(&[5, 1, 12, 12], polynomial.coefficients());
+
+        for n in [-1_000_000, -37, -1, 6, 100, 1_000_000] {
+            assert_eq!(f(n), polynomial.at(n.try_into()?)?);
+        }
+
+        assert_eq!(f(5 + 1_000), polynomial.forwards(1_000)?);
+        assert_eq!(f(-1_000), polynomial.backwards(1_000)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_constant_and_zero() -> Result<()> {
+        let constant = Polynomial::fit(&[4, 4])?;
+        let zero = Polynomial::fit(&[0, 0, 0])?;
+
+        assert_eq!(0, constant.degree());
+        assert_eq!(4, constant.at(-9)?);
+        assert_eq!(0, zero.degree());
+        assert_eq!(0, zero.at(42)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_polynomial() {
+        assert!(Polynomial::fit(&[1, 2, 4, 8, 16, 32]).is_err());
+        assert!(Polynomial::fit(&[1, 5]).is_err());
+        assert!(Polynomial::fit(&[]).is_err());
+    }
+
+    #[test]
+    fn test_single_value() -> Result<()> {
+        for value in [3, 0, -7, i64::MIN, i64::MAX] {
+            let polynomial = Polynomial::fit(&[value])?;
+
+            assert_eq!(0, polynomial.degree());
+            assert_eq!(i128::from(value), polynomial.at(0)?);
+            assert_eq!(i128::from(value), polynomial.forwards(1)?);
+            assert_eq!(i128::from(value), polynomial.backwards(1_000)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> Result<()> {
+        let square = Polynomial::fit(&[0, 1, 4, 9])?;
+        let cube = Polynomial::fit(&[0, 1, 8, 27, 64])?;
+
+        assert_eq!(i128::from(i64::MAX).pow(2), square.at(i64::MAX)?);
+        assert!(cube.at(i64::MAX).is_err());
+        assert!(cube.forwards(i64::MAX).is_err());
+
+        Ok(())
+    }
+
+    fn evaluate(coefficients: &[i64], x: i64) -> i128 {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0, |value, &a| value * i128::from(x) + i128::from(a))
+    }
+
+    /// Generates the coefficients of a polynomial and a history sampling it
+    /// at enough indices to pin it down
+    fn history() -> impl Strategy<Value = (Vec<i64>, Vec<i64>)> {
+        (prop::collection::vec(-20..20_i64, 1..6), 2..8_usize).prop_map(|(coefficients, extra)| {
+            let values = (0..i64::try_from(coefficients.len() + extra).unwrap_or_default())
+                .map(|x| i64::try_from(evaluate(&coefficients, x)).unwrap_or_default())
+                .collect();
+
+            (coefficients, values)
+        })
+    }
+
+    /// Extrapolates both ways by building the table of differences, as the
+    /// puzzle describes
+    fn naive_extrapolation(values: &[i64]) -> (i128, i128) {
+        let mut rows = vec![values.iter().copied().map(i128::from).collect_vec()];
+
+        while rows[rows.len() - 1].iter().any(|&n| n != 0) {
+            let next = rows[rows.len() - 1]
+                .iter()
                 .tuple_windows()
                 .map(|(a, b)| b - a)
-                .collect::<Vec<i32>>(),
-        )
-    })
+                .collect();
+            rows.push(next);
+        }
+
+        let next = rows.iter().filter_map(|row| row.last()).sum();
+        let previous = rows
+            .iter()
+            .rev()
+            .filter_map(|row| row.first())
+            .fold(0, |below, first| first - below);
+
+        (next, previous)
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_report(histories in prop::collection::vec(history(), 1..10)) {
+            let input = histories
+                .iter()
+                .map(|(_, values)| values.iter().join(" "))
+                .join("\n");
+
+            let parsed = parse_oasis_report(&input)
+                .collect::<Result<Vec<_>>>()
+                .expect("Generated report is valid");
+
+            prop_assert_eq!(histories.into_iter().map(|(_, values)| values).collect_vec(), parsed);
+        }
+
+        #[test]
+        fn test_fit_matches_generating_polynomial((coefficients, values) in history()) {
+            let polynomial = Polynomial::fit(&values).expect("Generated history is a polynomial");
+            let samples = i64::try_from(values.len()).unwrap_or_default();
+
+            for x in -5..samples + 5 {
+                prop_assert_eq!(evaluate(&coefficients, x), polynomial.at(x).expect("Value fits"));
+            }
+
+            prop_assert!(polynomial.degree() < coefficients.len());
+            prop_assert_eq!(
+                naive_extrapolation(&values),
+                (
+                    polynomial.forwards(1).expect("Value fits"),
+                    polynomial.backwards(1).expect("Value fits")
+                )
+            );
+        }
+    }
 }
@@ -1,9 +1,15 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,6 +19,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<usize> {
     let (pipes, start) = day_10::parse_pipes(input)?;
+    timing::parsed();
 
     let result = pipes.pipe_loop(start).count() / 2;
 
@@ -1,10 +1,15 @@
 use anyhow::Result;
-use day_10::Pipe;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -12,43 +17,11 @@ fn main() -> Result<()> {
 }
 
 #[inline]
-fn process(input: &str) -> Result<u32> {
+fn process(input: &str) -> Result<usize> {
     let (pipes, start) = day_10::parse_pipes(input)?;
+    timing::parsed();
 
-    let main_loop = pipes.pipe_loop_matrix(start);
-
-    let mut result = 0;
-
-    for (row, is_loop_row) in pipes.matrix.into_iter().zip(main_loop) {
-        let mut loop_counter = 0;
-        let mut last_bend: Option<Pipe> = None;
-
-        for (pipe, is_loop) in row.into_iter().zip(is_loop_row) {
-            if is_loop {
-                match pipe {
-                    Some(Pipe::Horizontal) | None => (),
-                    Some(Pipe::Vertical) => loop_counter += 1,
-                    Some(bend) => {
-                        last_bend = last_bend.map_or(Some(bend), |last_bend| {
-                            let continue_vertical = !bend
-                                .openings()
-                                .iter()
-                                .any(|o| last_bend.openings().contains(o));
-
-                            if continue_vertical {
-                                loop_counter += 1;
-                            }
-                            None
-                        });
-                    }
-                }
-            } else if loop_counter % 2 == 1 {
-                result += 1;
-            }
-        }
-    }
-
-    Ok(result)
+    Ok(pipes.enclosed_area(start))
 }
 
 #[cfg(test)]
@@ -96,7 +69,7 @@ L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L",
         10
     )]
-    fn test_example(#[case] input: &str, #[case] expected: u32) {
+    fn test_example(#[case] input: &str, #[case] expected: usize) {
         let result = process(input).unwrap();
 
         assert_eq!(expected, result);
@@ -1,6 +1,7 @@
+use std::collections::HashSet;
 use std::fmt::{Display, Write};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use itertools::Itertools;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -66,6 +67,34 @@ impl Pipe {
     }
 }
 
+impl Pipe {
+    /// The pipe as written in the map
+    #[must_use]
+    pub const fn to_char(self) -> char {
+        match self {
+            Self::Vertical => '|',
+            Self::Horizontal => '-',
+            Self::BendNE => 'L',
+            Self::BendNW => 'J',
+            Self::BendSE => 'F',
+            Self::BendSW => '7',
+        }
+    }
+
+    /// The pipe as a box-drawing character
+    #[must_use]
+    pub const fn box_drawing(self) -> char {
+        match self {
+            Self::Vertical => '│',
+            Self::Horizontal => '─',
+            Self::BendNE => '└',
+            Self::BendNW => '┘',
+            Self::BendSE => '┌',
+            Self::BendSW => '┐',
+        }
+    }
+}
+
 impl TryFrom<(Coordinate, Coordinate)> for Pipe {
     type Error = anyhow::Error;
 
@@ -120,19 +149,40 @@ impl TryFrom<char> for Pipe {
 
 impl Display for Pipe {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Vertical => f.write_char('|'),
-            Self::Horizontal => f.write_char('-'),
-            Self::BendNE => f.write_char('L'),
-            Self::BendNW => f.write_char('J'),
-            Self::BendSE => f.write_char('F'),
-            Self::BendSW => f.write_char('7'),
-        }
+        f.write_char(self.to_char())
     }
 }
 
 type Position = (usize, usize);
 
+/// Pipes connected to each other through openings that face each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// Tiles of the component in reading order
+    pub tiles: Vec<Position>,
+    /// Whether every pipe is connected at both ends, forming a closed loop
+    /// rather than an open chain
+    pub is_loop: bool,
+}
+
+impl Component {
+    #[must_use]
+    pub const fn length(&self) -> usize {
+        self.tiles.len()
+    }
+}
+
+/// How to render the loop of a pipe map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// The loop in box-drawing characters, with `I` on tiles inside it and
+    /// `O` on tiles outside it
+    BoxDrawing,
+    /// The loop in the input characters with `S` at the start and every
+    /// other tile empty, it parses back into the same loop
+    Cleaned,
+}
+
 pub struct Pipes {
     pub matrix: Vec<Vec<Option<Pipe>>>,
     length: usize,
@@ -146,6 +196,11 @@ impl TryFrom<Vec<Vec<Option<Pipe>>>> for Pipes {
         let height = matrix.len();
         let length = matrix.first().context("Must not be empty")?.len();
 
+        ensure!(
+            matrix.iter().all(|row| row.len() == length),
+            "All rows must have the same length"
+        );
+
         Ok(Self {
             matrix,
             length,
@@ -183,6 +238,90 @@ impl Pipes {
         }
     }
 
+    /// Gets the neighbouring pipes with an opening facing back at the pipe
+    pub fn connections(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.get(position)
+            .map(|pipe| pipe.openings())
+            .into_iter()
+            .flatten()
+            .filter_map(move |opening| {
+                let next = opening.move_position(position)?;
+
+                self.get(next)?
+                    .openings()
+                    .contains(&opening.contrary())
+                    .then_some(next)
+            })
+    }
+
+    /// Whether following the pipes from `start` leads back to it
+    #[must_use]
+    pub fn closes_loop(&self, start: Position) -> bool {
+        let Some(pipe) = self.get(start) else {
+            return false;
+        };
+
+        let mut position = start;
+        let mut coordinate = pipe.openings()[0];
+
+        for _ in 0..self.length * self.height {
+            let Some(next) = self.get_next(position, coordinate) else {
+                return false;
+            };
+
+            (position, coordinate) = next;
+
+            if position == start {
+                return pipe.get_exit(&coordinate).is_some();
+            }
+        }
+
+        false
+    }
+
+    /// Finds every group of connected pipes, telling closed loops from open chains
+    #[must_use]
+    pub fn components(&self) -> Vec<Component> {
+        let mut seen = vec![vec![false; self.length]; self.height];
+        let mut components = Vec::new();
+
+        for (x, y) in (0..self.height)
+            .cartesian_product(0..self.length)
+            .map(|(y, x)| (x, y))
+        {
+            if seen[y][x] || self.get((x, y)).is_none() {
+                continue;
+            }
+
+            seen[y][x] = true;
+
+            let mut tiles = Vec::new();
+            let mut pending = vec![(x, y)];
+            let mut is_loop = true;
+
+            while let Some(position) = pending.pop() {
+                let connections = self.connections(position).collect_vec();
+
+                is_loop &= connections.len() == 2;
+
+                for (x, y) in connections {
+                    if !seen[y][x] {
+                        seen[y][x] = true;
+                        pending.push((x, y));
+                    }
+                }
+
+                tiles.push(position);
+            }
+
+            tiles.sort_unstable_by_key(|&(x, y)| (y, x));
+
+            components.push(Component { tiles, is_loop });
+        }
+
+        components
+    }
+
     /// # Precondition
     /// The pipes matrix must have a loop and the start position must be part of that loop
     ///
@@ -209,6 +348,105 @@ impl Pipes {
 
         matrix
     }
+
+    /// Counts the tiles enclosed by the loop through `start`
+    ///
+    /// The shoelace formula over the loop tiles gives the area of the loop
+    /// and Pick's theorem turns it into the tiles strictly inside.
+    #[must_use]
+    pub fn enclosed_area(&self, start: Position) -> usize {
+        let vertices = self.pipe_loop(start).collect_vec();
+
+        let (clockwise, counterclockwise) = vertices.iter().circular_tuple_windows().fold(
+            (0, 0),
+            |(clockwise, counterclockwise), (&(x0, y0), &(x1, y1))| {
+                (clockwise + x0 * y1, counterclockwise + x1 * y0)
+            },
+        );
+        let double_area = clockwise.abs_diff(counterclockwise);
+
+        (double_area + 2).saturating_sub(vertices.len()) / 2
+    }
+
+    /// Gets the tiles enclosed by the loop through `start`
+    ///
+    /// The loop is drawn on a grid of double resolution with a padding
+    /// border, so squeezing between pipes becomes a gap of empty cells and
+    /// a flood fill from the border reaches every tile outside.
+    #[must_use]
+    pub fn enclosed_tiles(&self, start: Position) -> HashSet<Position> {
+        let (length, height) = (2 * self.length + 1, 2 * self.height + 1);
+        let mut walls = vec![vec![false; length]; height];
+
+        for ((x0, y0), (x1, y1)) in self
+            .pipe_loop(start)
+            .collect_vec()
+            .into_iter()
+            .circular_tuple_windows()
+        {
+            walls[2 * y0 + 1][2 * x0 + 1] = true;
+            walls[y0 + y1 + 1][x0 + x1 + 1] = true;
+        }
+
+        let mut outside = vec![vec![false; length]; height];
+        let mut pending = vec![(0, 0)];
+        outside[0][0] = true;
+
+        while let Some(position) = pending.pop() {
+            for coordinate in [
+                Coordinate::North,
+                Coordinate::South,
+                Coordinate::East,
+                Coordinate::West,
+            ] {
+                let Some((x, y)) = coordinate.move_position(position) else {
+                    continue;
+                };
+
+                if x < length && y < height && !walls[y][x] && !outside[y][x] {
+                    outside[y][x] = true;
+                    pending.push((x, y));
+                }
+            }
+        }
+
+        (0..self.height)
+            .cartesian_product(0..self.length)
+            .map(|(y, x)| (x, y))
+            .filter(|&(x, y)| !walls[2 * y + 1][2 * x + 1] && !outside[2 * y + 1][2 * x + 1])
+            .collect()
+    }
+
+    /// Renders the loop through `start`, leaving out the junk pipes
+    #[must_use]
+    pub fn render(&self, start: Position, style: Style) -> String {
+        let main_loop = self.pipe_loop_matrix(start);
+        let inside = match style {
+            Style::BoxDrawing => self.enclosed_tiles(start),
+            Style::Cleaned => HashSet::new(),
+        };
+
+        let mut render = String::new();
+
+        for (y, row) in self.matrix.iter().enumerate() {
+            for (x, pipe) in row.iter().enumerate() {
+                let pipe = pipe.filter(|_| main_loop[y][x]);
+
+                render.push(match (style, pipe) {
+                    (Style::Cleaned, _) if (x, y) == start => 'S',
+                    (Style::Cleaned, Some(pipe)) => pipe.to_char(),
+                    (Style::Cleaned, None) => '.',
+                    (Style::BoxDrawing, Some(pipe)) => pipe.box_drawing(),
+                    (Style::BoxDrawing, None) if inside.contains(&(x, y)) => 'I',
+                    (Style::BoxDrawing, None) => 'O',
+                });
+            }
+
+            render.push('\n');
+        }
+
+        render
+    }
 }
 
 impl Display for Pipes {
@@ -257,13 +495,14 @@ pub fn parse_pipes(input: &str) -> Result<(Pipes, Position)> {
     let mut pipes = Pipes::try_from(matrix)?;
 
     let start = start.context("Must have found start")?;
-    let start_pipe = get_start_pipe(&pipes, start).context("Start pipe must form a loop")?;
-    pipes.matrix[start.1][start.0].replace(start_pipe);
+    set_start_pipe(&mut pipes, start)?;
 
     Ok((pipes, start))
 }
 
-fn get_start_pipe(pipes: &Pipes, start: Position) -> Option<Pipe> {
+/// Places the start pipe connecting two of the pipes facing the start, trying
+/// every pair until one closes a loop
+fn set_start_pipe(pipes: &mut Pipes, (x, y): Position) -> Result<()> {
     static COORDINATES: [Coordinate; 4] = [
         Coordinate::North,
         Coordinate::East,
@@ -273,14 +512,364 @@ fn get_start_pipe(pipes: &Pipes, start: Position) -> Option<Pipe> {
 
     let valid_coordinates = COORDINATES
         .into_iter()
-        .filter_map(|c| {
-            let position = c.move_position(start)?;
+        .filter(|c| {
+            c.move_position((x, y))
+                .and_then(|position| pipes.get(position))
+                .is_some_and(|pipe| pipe.openings().contains(&c.contrary()))
+        })
+        .collect_vec();
 
-            let pipe = pipes.get(position)?;
+    for (a, b) in valid_coordinates.into_iter().tuple_combinations() {
+        pipes.matrix[y][x] = Some(Pipe::try_from((a, b))?);
 
-            pipe.openings().contains(&c.contrary()).then_some(c)
-        })
-        .collect_tuple::<(_, _)>()?;
+        if pipes.closes_loop((x, y)) {
+            return Ok(());
+        }
+    }
+
+    pipes.matrix[y][x] = None;
+
+    bail!("Start pipe must form a loop")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    /// A generated pipe map with a rectangular loop, the start position and the loop tiles
+    type Maze = (String, Position, HashSet<Position>);
+
+    /// Generates a pipe map whose main loop is a rectangle surrounded by junk pipes.
+    /// Junk is never placed next to the start so it always has exactly two connections
+    fn maze_input() -> impl Strategy<Value = Maze> {
+        (
+            (0..3_usize, 0..3_usize),
+            (2..8_usize, 2..8_usize),
+            (0..3_usize, 0..3_usize),
+            any::<Index>(),
+            prop::collection::vec(
+                prop::sample::select(&['|', '-', 'L', 'J', '7', 'F', '.'][..]),
+                196,
+            ),
+        )
+            .prop_map(
+                |((left, top), (width, height), (right, bottom), start, junk)| {
+                    let (x0, y0) = (left, top);
+                    let (x1, y1) = (left + width - 1, top + height - 1);
+
+                    let border = (x0..x1)
+                        .map(|x| (x, y0))
+                        .chain((y0..y1).map(|y| (x1, y)))
+                        .chain((x0 + 1..=x1).rev().map(|x| (x, y1)))
+                        .chain((y0 + 1..=y1).rev().map(|y| (x0, y)))
+                        .collect::<Vec<_>>();
+
+                    let start = border[start.index(border.len())];
+                    let is_next_to_start =
+                        |(x, y): Position| x.abs_diff(start.0) + y.abs_diff(start.1) == 1;
+
+                    let input = (0..top + height + bottom)
+                        .map(|y| {
+                            (0..left + width + right)
+                                .map(|x| match (x, y) {
+                                    p if p == start => 'S',
+                                    (x, y) if (x, y) == (x0, y0) => 'F',
+                                    (x, y) if (x, y) == (x1, y0) => '7',
+                                    (x, y) if (x, y) == (x0, y1) => 'L',
+                                    (x, y) if (x, y) == (x1, y1) => 'J',
+                                    (x, y) if (y == y0 || y == y1) && (x0..=x1).contains(&x) => '-',
+                                    (x, y) if (x == x0 || x == x1) && (y0..=y1).contains(&y) => '|',
+                                    p if is_next_to_start(p) => '.',
+                                    (x, y) => junk[(y * 14 + x) % junk.len()],
+                                })
+                                .collect::<String>()
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+
+                    (input, start, border.into_iter().collect())
+                },
+            )
+    }
+
+    #[rstest::rstest]
+    #[case(
+        "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........",
+        &[(2, 6), (3, 6), (7, 6), (8, 6)]
+    )]
+    #[case(
+        "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........",
+        &[(2, 6), (3, 6), (6, 6), (7, 6)]
+    )]
+    #[case("S7\nLJ", &[])]
+    #[case("S-7\n|.|\nL-J", &[(1, 1)])]
+    fn test_enclosed_tiles(#[case] input: &str, #[case] expected: &[Position]) -> Result<()> {
+        let (pipes, start) = parse_pipes(input)?;
+
+        let tiles = pipes.enclosed_tiles(start);
+
+        assert_eq!(expected.iter().copied().collect::<HashSet<_>>(), tiles);
+        assert_eq!(expected.len(), pipes.enclosed_area(start));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_enclosed_tiles_with_junk() -> Result<()> {
+        let input = "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
 
-    Pipe::try_from(valid_coordinates).ok()
+        let (pipes, start) = parse_pipes(input)?;
+
+        let tiles = pipes.enclosed_tiles(start);
+        let main_loop = pipes.pipe_loop_matrix(start);
+
+        assert_eq!(10, tiles.len());
+        assert_eq!(10, pipes.enclosed_area(start));
+        assert!(tiles.iter().all(|&(x, y)| !main_loop[y][x]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let input = "7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ";
+
+        let (pipes, start) = parse_pipes(input)?;
+
+        assert_eq!(
+            "OO┌┐O
+O┌┘│O
+┌┘I└┐
+│┌──┘
+└┘OOO
+",
+            pipes.render(start, Style::BoxDrawing)
+        );
+        assert_eq!(
+            "..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+",
+            pipes.render(start, Style::Cleaned)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_inside() -> Result<()> {
+        let input = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+        let (pipes, start) = parse_pipes(input)?;
+
+        assert_eq!(
+            "OOOOOOOOOOO
+O┌───────┐O
+O│┌─────┐│O
+O││OOOOO││O
+O││OOOOO││O
+O│└─┐O┌─┘│O
+O│II│O│II│O
+O└──┘O└──┘O
+OOOOOOOOOOO
+",
+            pipes.render(start, Style::BoxDrawing)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_start_with_many_neighbours() -> Result<()> {
+        let input = ".|...
+-S-7.
+.|.|.
+.L-J.";
+
+        let (pipes, start) = parse_pipes(input)?;
+
+        assert_eq!((1, 1), start);
+        assert!(matches!(pipes.get(start), Some(Pipe::BendSE)));
+        assert_eq!(8, pipes.pipe_loop(start).count());
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case("S-7\n|..\nL-J")]
+    #[case("S--\n|..\nL-J")]
+    #[case("S.\n..")]
+    fn test_start_without_loop(#[case] input: &str) {
+        assert!(parse_pipes(input).is_err());
+    }
+
+    #[test]
+    fn test_components() -> Result<()> {
+        let input = "S7.F-7
+LJ.|.|
+F-.L-J
+|.....";
+
+        let (pipes, _) = parse_pipes(input)?;
+
+        assert_eq!(
+            vec![
+                Component {
+                    tiles: vec![(0, 0), (1, 0), (0, 1), (1, 1)],
+                    is_loop: true,
+                },
+                Component {
+                    tiles: vec![
+                        (3, 0),
+                        (4, 0),
+                        (5, 0),
+                        (3, 1),
+                        (5, 1),
+                        (3, 2),
+                        (4, 2),
+                        (5, 2)
+                    ],
+                    is_loop: true,
+                },
+                Component {
+                    tiles: vec![(0, 2), (1, 2), (0, 3)],
+                    is_loop: false,
+                },
+            ],
+            pipes.components()
+        );
+        assert_eq!(
+            vec![4, 8, 3],
+            pipes
+                .components()
+                .iter()
+                .map(Component::length)
+                .collect_vec()
+        );
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_finds_start((input, start, _) in maze_input()) {
+            let (_, result) = parse_pipes(&input).expect("Generated maze is valid");
+
+            prop_assert_eq!(start, result);
+        }
+
+        #[test]
+        fn test_loop_visits_every_loop_tile_once((input, _, border) in maze_input()) {
+            let (pipes, start) = parse_pipes(&input).expect("Generated maze is valid");
+
+            let pipe_loop = pipes.pipe_loop(start).collect::<Vec<_>>();
+            let visited = pipe_loop.iter().copied().collect::<HashSet<_>>();
+
+            prop_assert_eq!(border.len(), pipe_loop.len());
+            prop_assert_eq!(border, visited);
+        }
+
+        #[test]
+        fn test_loop_matrix_marks_loop((input, _, border) in maze_input()) {
+            let (pipes, start) = parse_pipes(&input).expect("Generated maze is valid");
+
+            let matrix = pipes.pipe_loop_matrix(start);
+
+            for (y, row) in matrix.into_iter().enumerate() {
+                for (x, is_loop) in row.into_iter().enumerate() {
+                    prop_assert_eq!(border.contains(&(x, y)), is_loop);
+                }
+            }
+        }
+
+        #[test]
+        fn test_cleaned_render_parses_back((input, _, border) in maze_input()) {
+            let (pipes, start) = parse_pipes(&input).expect("Generated maze is valid");
+
+            let cleaned = pipes.render(start, Style::Cleaned);
+            let (cleaned_pipes, cleaned_start) = parse_pipes(&cleaned).expect("Cleaned map is valid");
+
+            let kept = cleaned_pipes
+                .matrix
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, pipe)| pipe.is_some()).map(move |(x, _)| (x, y)))
+                .collect::<HashSet<_>>();
+
+            prop_assert_eq!(start, cleaned_start);
+            prop_assert_eq!(&border, &kept);
+            prop_assert_eq!(cleaned, cleaned_pipes.render(cleaned_start, Style::Cleaned));
+        }
+
+        #[test]
+        fn test_components_include_main_loop((input, start, border) in maze_input()) {
+            let (pipes, _) = parse_pipes(&input).expect("Generated maze is valid");
+
+            let components = pipes.components();
+            let main = components
+                .iter()
+                .find(|component| component.tiles.contains(&start))
+                .expect("Start is in a component");
+
+            prop_assert!(main.is_loop);
+            prop_assert_eq!(border, main.tiles.iter().copied().collect::<HashSet<_>>());
+            prop_assert!(components.iter().all(|component| !component.is_loop || component.length() >= 4));
+        }
+
+        #[test]
+        fn test_enclosed_tiles_fill_rectangle((input, _, border) in maze_input()) {
+            let (pipes, start) = parse_pipes(&input).expect("Generated maze is valid");
+
+            let (min_x, max_x) = border.iter().map(|&(x, _)| x).minmax().into_option().expect("Border is not empty");
+            let (min_y, max_y) = border.iter().map(|&(_, y)| y).minmax().into_option().expect("Border is not empty");
+            let inside = (min_x + 1..max_x)
+                .cartesian_product(min_y + 1..max_y)
+                .collect::<HashSet<_>>();
+
+            prop_assert_eq!(inside.len(), pipes.enclosed_area(start));
+            prop_assert_eq!(inside, pipes.enclosed_tiles(start));
+        }
+    }
 }
@@ -1,16 +1,27 @@
+use anyhow::Result;
+
 use day_11::Galaxy;
 
-fn main() {
-    let input = include_str!("../../input.txt");
+fn main() -> Result<()> {
+    timing::start();
+
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
 
-    let result = process(input);
+    let result = process(&input);
+    timing::solved();
 
     println!("{result}");
+
+    Ok(())
 }
 
 #[inline]
 fn process(input: &str) -> usize {
     let mut galaxies = day_11::parse_cosmos(input).collect::<Vec<Galaxy>>();
+    timing::parsed();
 
     day_11::expand_galaxy(&mut galaxies, 2);
 
@@ -1,23 +1,11 @@
-use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 
-use itertools::Itertools;
-
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Galaxy {
     pub x: usize,
     pub y: usize,
 }
 
-impl Galaxy {
-    pub fn x_mut(&mut self) -> &mut usize {
-        &mut self.x
-    }
-
-    pub fn y_mut(&mut self) -> &mut usize {
-        &mut self.y
-    }
-}
-
 pub fn parse_cosmos(input: &str) -> impl Iterator<Item = Galaxy> + '_ {
     input.lines().enumerate().flat_map(|(y, l)| {
         l.chars().enumerate().filter_map(move |(x, c)| match c {
@@ -27,41 +15,255 @@ pub fn parse_cosmos(input: &str) -> impl Iterator<Item = Galaxy> + '_ {
     })
 }
 
-pub fn expand_galaxy(galaxies: &mut [Galaxy], expansion_size: usize) {
-    expand_galaxy_internal(galaxies, Galaxy::x_mut, expansion_size);
-    expand_galaxy_internal(galaxies, Galaxy::y_mut, expansion_size);
+/// The empty columns and rows of a cosmos, each one growing into as many
+/// columns or rows as its axis' expansion factor
+#[derive(Debug, Clone)]
+pub struct Universe {
+    empty_columns: Vec<usize>,
+    empty_rows: Vec<usize>,
+    x_expansion: usize,
+    y_expansion: usize,
 }
 
-fn expand_galaxy_internal<F>(galaxies: &mut [Galaxy], direction: F, expansion_size: usize)
-where
-    F: Fn(&mut Galaxy) -> &mut usize,
-{
-    let mut galaxies_by_line: BTreeMap<usize, Vec<&mut Galaxy>> =
-        galaxies.iter_mut().fold(BTreeMap::new(), |mut map, g| {
-            map.entry(*direction(g)).or_default().push(g);
-            map
-        });
+impl Universe {
+    #[must_use]
+    pub fn new(galaxies: &[Galaxy], x_expansion: usize, y_expansion: usize) -> Self {
+        Self {
+            empty_columns: empty_lines(galaxies.iter().map(|g| g.x)),
+            empty_rows: empty_lines(galaxies.iter().map(|g| g.y)),
+            x_expansion,
+            y_expansion,
+        }
+    }
 
-    let limit = galaxies_by_line
-        .keys()
-        .max()
-        .expect("One galaxy must exist");
+    /// Where a galaxy ends up after the expansion
+    #[must_use]
+    pub fn expanded(&self, galaxy: &Galaxy) -> Galaxy {
+        Galaxy {
+            x: expand(&self.empty_columns, galaxy.x, self.x_expansion),
+            y: expand(&self.empty_rows, galaxy.y, self.y_expansion),
+        }
+    }
 
-    let mut expanded = 0;
+    /// Shortest path between two galaxies after the expansion
+    #[must_use]
+    pub fn distance(&self, a: &Galaxy, b: &Galaxy) -> usize {
+        let (a, b) = (self.expanded(a), self.expanded(b));
 
-    for i in 0..=*limit {
-        match galaxies_by_line.get_mut(&i) {
-            Some(gs) => gs.iter_mut().for_each(|g| *direction(g) += expanded),
-            None => expanded += expansion_size - 1,
-        }
+        a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
     }
 }
 
+fn empty_lines(coordinates: impl Iterator<Item = usize>) -> Vec<usize> {
+    let occupied = coordinates.collect::<BTreeSet<_>>();
+    let limit = occupied.last().copied().unwrap_or_default();
+
+    (0..limit).filter(|i| !occupied.contains(i)).collect()
+}
+
+fn expand(empty_lines: &[usize], coordinate: usize, expansion: usize) -> usize {
+    let before = empty_lines.partition_point(|&line| line < coordinate);
+
+    // NOTE: the empty lines before are all below the coordinate, so this never underflows
+    coordinate - before + before * expansion
+}
+
+pub fn expand_galaxy(galaxies: &mut [Galaxy], expansion_size: usize) {
+    expand_galaxy_axes(galaxies, expansion_size, expansion_size);
+}
+
+/// Expands the galaxies with a different factor for columns and rows
+pub fn expand_galaxy_axes(galaxies: &mut [Galaxy], x_expansion: usize, y_expansion: usize) {
+    let universe = Universe::new(galaxies, x_expansion, y_expansion);
+
+    for galaxy in galaxies {
+        *galaxy = universe.expanded(galaxy);
+    }
+}
+
+/// Sums the distances between every pair of galaxies
+///
+/// Along each axis, the i-th smallest coordinate is the far end of i pairs,
+/// adding `i * c` minus the sum of the coordinates below it.
 #[must_use]
 pub fn shortest_paths(galaxies: &[Galaxy]) -> usize {
-    galaxies
-        .iter()
-        .tuple_combinations()
-        .map(|(g1, g2)| g1.x.abs_diff(g2.x) + g1.y.abs_diff(g2.y))
-        .sum()
+    axis_distances(galaxies.iter().map(|g| g.x).collect())
+        + axis_distances(galaxies.iter().map(|g| g.y).collect())
+}
+
+fn axis_distances(mut coordinates: Vec<usize>) -> usize {
+    coordinates.sort_unstable();
+
+    coordinates
+        .into_iter()
+        .enumerate()
+        .fold((0, 0), |(sum, below), (i, coordinate)| {
+            (sum + coordinate * i - below, below + coordinate)
+        })
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    fn brute_force(galaxies: &[Galaxy]) -> usize {
+        galaxies
+            .iter()
+            .tuple_combinations()
+            .map(|(g1, g2)| g1.x.abs_diff(g2.x) + g1.y.abs_diff(g2.y))
+            .sum()
+    }
+
+    #[test]
+    fn test_shortest_paths_matches_brute_force() {
+        let mut galaxies = parse_cosmos(EXAMPLE).collect_vec();
+
+        assert_eq!(brute_force(&galaxies), shortest_paths(&galaxies));
+
+        expand_galaxy(&mut galaxies, 1_000);
+
+        assert_eq!(brute_force(&galaxies), shortest_paths(&galaxies));
+
+        // NOTE: a pseudo-random scatter with repeated coordinates
+        let galaxies = (0..500_usize)
+            .map(|i| Galaxy {
+                x: i * 7_919 % 613,
+                y: i * i % 97,
+            })
+            .collect_vec();
+
+        assert_eq!(brute_force(&galaxies), shortest_paths(&galaxies));
+    }
+
+    #[test]
+    fn test_empty_and_single() {
+        let mut galaxies = vec![];
+
+        expand_galaxy(&mut galaxies, 2);
+
+        assert_eq!(0, shortest_paths(&galaxies));
+        assert_eq!(0, shortest_paths(&[Galaxy { x: 3, y: 4 }]));
+    }
+
+    #[test]
+    fn test_distance() {
+        let galaxies = parse_cosmos(EXAMPLE).collect_vec();
+        let universe = Universe::new(&galaxies, 2, 2);
+
+        assert_eq!(9, universe.distance(&galaxies[4], &galaxies[8]));
+        assert_eq!(15, universe.distance(&galaxies[0], &galaxies[6]));
+        assert_eq!(17, universe.distance(&galaxies[2], &galaxies[5]));
+        assert_eq!(5, universe.distance(&galaxies[7], &galaxies[8]));
+        assert_eq!(parse_cosmos(EXAMPLE).collect_vec(), galaxies);
+    }
+
+    #[test]
+    fn test_expansion_per_axis() {
+        let galaxies = parse_cosmos(EXAMPLE).collect_vec();
+
+        for (x_expansion, y_expansion) in [(1, 1), (1, 10), (10, 1), (2, 100), (0, 3)] {
+            let mut expanded = galaxies.clone();
+            let universe = Universe::new(&galaxies, x_expansion, y_expansion);
+
+            expand_galaxy_axes(&mut expanded, x_expansion, y_expansion);
+
+            assert_eq!(
+                brute_force(&expanded),
+                galaxies
+                    .iter()
+                    .tuple_combinations()
+                    .map(|(a, b)| universe.distance(a, b))
+                    .sum::<usize>()
+            );
+        }
+
+        let mut expanded = galaxies;
+        expand_galaxy_axes(&mut expanded, 1, 3);
+
+        assert_eq!(Galaxy { x: 3, y: 0 }, expanded[0]);
+        assert_eq!(Galaxy { x: 0, y: 13 }, expanded[7]);
+        assert_eq!(Galaxy { x: 4, y: 13 }, expanded[8]);
+    }
+
+    /// Generates a sparse cosmos, where a cell is a galaxy one time in five
+    fn cosmos() -> impl Strategy<Value = Vec<Vec<bool>>> {
+        grids::grid(prop::bool::weighted(0.2), 1..15, 1..15)
+    }
+
+    fn cosmos_input(cosmos: &[Vec<bool>]) -> String {
+        cosmos
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&g| if g { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+
+    /// Expands the cosmos itself, repeating every empty column and row
+    fn naive_expansion(cosmos: &[Vec<bool>], x_expansion: usize, y_expansion: usize) -> String {
+        let empty_column = |x: usize| cosmos.iter().all(|row| !row[x]);
+
+        let rows = cosmos.iter().flat_map(|row| {
+            let times = if row.contains(&true) { 1 } else { y_expansion };
+
+            let row = (0..row.len())
+                .flat_map(|x| {
+                    let times = if empty_column(x) { x_expansion } else { 1 };
+                    std::iter::repeat_n(row[x], times)
+                })
+                .collect_vec();
+
+            std::iter::repeat_n(row, times)
+        });
+
+        cosmos_input(&rows.collect_vec())
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_cosmos(cosmos in cosmos()) {
+            let galaxies = parse_cosmos(&cosmos_input(&cosmos)).collect_vec();
+
+            let expected = cosmos
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.iter().positions(|&g| g).map(move |x| Galaxy { x, y }))
+                .collect_vec();
+
+            prop_assert_eq!(expected, galaxies);
+        }
+
+        #[test]
+        fn test_expansion_matches_expanded_cosmos(
+            cosmos in cosmos(),
+            x_expansion in 0..4_usize,
+            y_expansion in 0..4_usize,
+        ) {
+            let mut galaxies = parse_cosmos(&cosmos_input(&cosmos)).collect_vec();
+            let expanded = naive_expansion(&cosmos, x_expansion, y_expansion);
+            let expected = parse_cosmos(&expanded).collect_vec();
+
+            expand_galaxy_axes(&mut galaxies, x_expansion, y_expansion);
+
+            prop_assert_eq!(&expected, &galaxies);
+            prop_assert_eq!(brute_force(&galaxies), shortest_paths(&galaxies));
+        }
+    }
 }
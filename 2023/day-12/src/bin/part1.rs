@@ -1,10 +1,16 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -12,13 +18,15 @@ fn main() -> Result<()> {
 }
 
 #[inline]
-fn process(input: &str) -> Result<u64> {
+fn process(input: &str) -> Result<u128> {
     let records = day_12::parse_spring_records(input)?;
+    timing::parsed();
 
     let result = records
         .into_par_iter()
-        .map(day_12::unknown_spring_posibilities)
-        .sum();
+        .map(|record| day_12::unknown_spring_posibilities(&record))
+        .try_reduce(|| 0, u128::checked_add)
+        .context("Arrangements overflow")?;
 
     Ok(result)
 }
@@ -50,12 +58,12 @@ mod tests {
     #[case("????.#...#... 4,1,1", 1)]
     #[case("????.######..#####. 1,6,5", 4)]
     #[case("?###???????? 3,2,1", 10)]
-    fn test_each(#[case] input: &str, #[case] expected: u64) -> Result<()> {
+    fn test_each(#[case] input: &str, #[case] expected: u128) -> Result<()> {
         let record = day_12::parse_record(input)?;
 
-        let result = day_12::unknown_spring_posibilities(record);
+        let result = day_12::unknown_spring_posibilities(&record);
 
-        assert_eq!(expected, result);
+        assert_eq!(Some(expected), result);
 
         Ok(())
     }
@@ -1,10 +1,16 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -12,14 +18,15 @@ fn main() -> Result<()> {
 }
 
 #[inline]
-fn process(input: &str) -> Result<u64> {
+fn process(input: &str) -> Result<u128> {
     let records = day_12::parse_spring_records(input)?;
+    timing::parsed();
 
     let result = records
         .into_par_iter()
-        .map(|r| day_12::repeat_record(r, 5))
-        .map(day_12::unknown_spring_posibilities)
-        .sum();
+        .map(|(springs, groups)| day_12::spring_arrangements(&springs, &groups, 5))
+        .try_reduce(|| 0, u128::checked_add)
+        .context("Arrangements overflow")?;
 
     Ok(result)
 }
@@ -41,7 +48,7 @@ mod tests {
 
         let result = process(input).unwrap();
 
-        assert_eq!(525152, result);
+        assert_eq!(525_152, result);
     }
 
     #[rstest]
@@ -50,14 +57,13 @@ mod tests {
     #[case("?#?#?#?#?#?#?#? 1,3,1,6", 1)]
     #[case("????.#...#... 4,1,1", 16)]
     #[case("????.######..#####. 1,6,5", 2500)]
-    #[case("?###???????? 3,2,1", 506250)]
-    fn test_each(#[case] input: &str, #[case] expected: u64) -> Result<()> {
+    #[case("?###???????? 3,2,1", 506_250)]
+    fn test_each(#[case] input: &str, #[case] expected: u128) -> Result<()> {
         let record = day_12::parse_record(input)?;
 
-        let record = day_12::repeat_record(record, 5);
-        let result = day_12::unknown_spring_posibilities(record);
+        let result = day_12::spring_arrangements(&record.0, &record.1, 5);
 
-        assert_eq!(expected, result);
+        assert_eq!(Some(expected), result);
 
         Ok(())
     }
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
-
 use anyhow::{bail, Context, Result};
+use rand::{Rng, RngExt};
+
+pub mod nonogram;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Spring {
     Operational,
     Damaged,
@@ -26,89 +27,229 @@ pub type Record = (Vec<Spring>, Vec<u32>);
 
 #[must_use]
 pub fn repeat_record((springs, damaged): Record, times: usize) -> Record {
-    let damaged = std::iter::repeat(damaged).take(times).flatten().collect();
-    let springs = std::iter::repeat(springs)
-        .take(times)
+    let damaged = std::iter::repeat_n(damaged, times).flatten().collect();
+    let springs = std::iter::repeat_n(springs, times)
         .collect::<Vec<_>>()
         .join(&Spring::Unknown);
 
     (springs, damaged)
 }
 
+/// Counts the arrangements of a record, `None` if they overflow
+#[must_use]
+pub fn unknown_spring_posibilities((springs, groups): &Record) -> Option<u128> {
+    spring_arrangements(springs, groups, 1)
+}
+
+/// Counts the arrangements of the springs and groups repeated `times` times,
+/// with the copies of the springs joined by unknown springs
+///
+/// The springs are walked once without recursion, tracking for each number of
+/// finished groups and length of the current damaged run the ways to get
+/// there. The repeated springs and groups are never built, only the current
+/// and next tables are allocated. Returns `None` if the count overflows.
 #[must_use]
-pub fn unknown_spring_posibilities(record: Record) -> u64 {
-    let mut cache = HashMap::new();
+pub fn spring_arrangements(springs: &[Spring], groups: &[u32], times: usize) -> Option<u128> {
+    let group_count = groups.len() * times;
+    let length = (springs.len() + 1) * times.saturating_sub(1) + springs.len() * times.min(1);
+    let width = groups.iter().max().map_or(0, |&max| max as usize) + 1;
+
+    let spring = |i: usize| {
+        springs
+            .get(i % (springs.len() + 1))
+            .unwrap_or(&Spring::Unknown)
+    };
+    let group = |g: usize| groups[g % groups.len()] as usize;
+
+    // NOTE: ways[g * width + run] is the ways of having finished g groups with
+    // a damaged run of that length, only finished groups from lo to hi have any
+    let mut ways = vec![0_u128; (group_count + 1) * width];
+    let mut next = ways.clone();
+    let (mut lo, mut hi) = (0, 0);
+    ways[0] = 1;
+
+    for i in 0..length {
+        let spring = spring(i);
+        let top = (hi + 1).min(group_count);
+        next[lo * width..(top + 1) * width].fill(0);
+
+        for g in lo..=hi {
+            for run in 0..width {
+                let count = ways[g * width + run];
+
+                if count == 0 {
+                    continue;
+                }
+
+                if *spring != Spring::Damaged {
+                    if run == 0 {
+                        next[g * width] = next[g * width].checked_add(count)?;
+                    } else if g < group_count && run == group(g) {
+                        next[(g + 1) * width] = next[(g + 1) * width].checked_add(count)?;
+                    }
+                }
+
+                if *spring != Spring::Operational && g < group_count && run < group(g) {
+                    next[g * width + run + 1] = next[g * width + run + 1].checked_add(count)?;
+                }
+            }
+        }
+
+        let live = |g: &usize| next[g * width..(g + 1) * width].iter().any(|&n| n > 0);
+
+        let (Some(new_lo), Some(new_hi)) = ((lo..=top).find(live), (lo..=top).rfind(live)) else {
+            return Some(0);
+        };
+
+        (lo, hi) = (new_lo, new_hi);
+        std::mem::swap(&mut ways, &mut next);
+    }
+
+    let at = |g: usize, run: usize| {
+        if (lo..=hi).contains(&g) {
+            ways[g * width + run]
+        } else {
+            0
+        }
+    };
 
-    unknown_spring_posibilities_rec(Box::from(record.0), &record.1, 0, &mut cache)
+    at(group_count, 0).checked_add(group_count.checked_sub(1).map_or(0, |g| at(g, group(g))))
 }
 
-type CacheData<'a> = (Box<[Spring]>, &'a [u32], u32);
+/// Every concrete arrangement of a record, backed by a table counting the
+/// arrangements of each suffix of springs with each suffix of groups
+///
+/// Arrangements come in lexicographic order, operational before damaged, so
+/// `nth` and `sample` walk the table down to one without enumerating.
+#[derive(Debug, Clone)]
+pub struct Arrangements {
+    springs: Vec<Spring>,
+    groups: Vec<usize>,
+    /// `counts[i][g]`: arrangements of `springs[i..]` with `groups[g..]`,
+    /// where the spring before `i` is not damaged
+    counts: Vec<Vec<u128>>,
+}
 
-#[must_use]
-fn unknown_spring_posibilities_rec<'a>(
-    springs: Box<[Spring]>,
-    damaged_groups: &'a [u32],
-    damaged_count: u32,
-    cache: &mut HashMap<CacheData<'a>, u64>,
-) -> u64 {
-    let cache_data = (springs, damaged_groups, damaged_count);
-
-    cache.get(&cache_data).copied().unwrap_or_else(|| {
-        let (springs, damaged_groups, damaged_count) = cache_data;
-        let result = match springs.split_first() {
-            Some((Spring::Operational, springs)) => {
-                let springs = Box::from(springs);
-                if damaged_groups.first() == Some(&damaged_count) {
-                    unknown_spring_posibilities_rec(springs, &damaged_groups[1..], 0, cache)
-                } else if damaged_count == 0 {
-                    unknown_spring_posibilities_rec(springs, damaged_groups, 0, cache)
+impl Arrangements {
+    /// Builds the table for a record, `None` if a count does not fit in a `u128`
+    #[must_use]
+    pub fn new((springs, groups): &Record) -> Option<Self> {
+        let groups = groups
+            .iter()
+            .map(|&group| group as usize)
+            .collect::<Vec<_>>();
+        let length = springs.len();
+
+        // NOTE: springs that can be damaged from each index onwards, and
+        // whether a damaged spring remains
+        let mut runs = vec![0; length + 1];
+        let mut damaged_after = vec![false; length + 1];
+
+        for i in (0..length).rev() {
+            runs[i] = if springs[i] == Spring::Operational {
+                0
+            } else {
+                runs[i + 1] + 1
+            };
+            damaged_after[i] = damaged_after[i + 1] || springs[i] == Spring::Damaged;
+        }
+
+        let mut counts = vec![vec![0; groups.len() + 1]; length + 1];
+
+        for i in (0..=length).rev() {
+            counts[i][groups.len()] = u128::from(!damaged_after[i]);
+
+            for g in (0..groups.len()).rev() {
+                let operational = match springs.get(i) {
+                    Some(Spring::Operational | Spring::Unknown) => counts[i + 1][g],
+                    _ => 0,
+                };
+
+                let group = groups[g];
+                let end = i + group;
+                let damaged = if group > 0
+                    && runs[i] >= group
+                    && springs.get(end) != Some(&Spring::Damaged)
+                {
+                    counts[(end + 1).min(length)][g + 1]
                 } else {
                     0
-                }
+                };
+
+                counts[i][g] = operational.checked_add(damaged)?;
             }
-            Some((Spring::Damaged, springs)) => match damaged_groups.first() {
-                None => 0,
-                Some(&group) if group == damaged_count => 0,
-                _ => unknown_spring_posibilities_rec(
-                    Box::from(springs),
-                    damaged_groups,
-                    damaged_count + 1,
-                    cache,
-                ),
-            },
-            Some((Spring::Unknown, _)) => {
-                // Divide into damaged and operational posibilities
-                let mut springs_clone = springs.to_vec();
-
-                springs_clone[0] = Spring::Damaged;
-                let damaged_posibilities = unknown_spring_posibilities_rec(
-                    Box::from(springs_clone),
-                    damaged_groups,
-                    damaged_count,
-                    cache,
-                );
-
-                let mut springs_clone = springs.to_vec();
-                springs_clone[0] = Spring::Operational;
-
-                let operational_posibilities = unknown_spring_posibilities_rec(
-                    Box::from(springs_clone),
-                    damaged_groups,
-                    damaged_count,
-                    cache,
-                );
-
-                damaged_posibilities + operational_posibilities
+        }
+
+        Some(Self {
+            springs: springs.clone(),
+            groups,
+            counts,
+        })
+    }
+
+    /// Number of arrangements
+    #[must_use]
+    pub fn count(&self) -> u128 {
+        self.counts[0][0]
+    }
+
+    /// Gets the arrangement at an index, in the order of `iter`
+    #[must_use]
+    pub fn nth(&self, mut index: u128) -> Option<Vec<Spring>> {
+        if index >= self.count() {
+            return None;
+        }
+
+        let length = self.springs.len();
+        let mut arrangement = Vec::with_capacity(length);
+        let (mut i, mut g) = (0, 0);
+
+        while i < length {
+            if g == self.groups.len() {
+                arrangement.resize(length, Spring::Operational);
+                break;
             }
-            None => u64::from(
-                damaged_groups.is_empty()
-                    || (damaged_groups.len() == 1 && damaged_groups[0] == damaged_count),
-            ),
-        };
 
-        cache.insert((springs, damaged_groups, damaged_count), result);
-        result
-    })
+            let operational = match self.springs[i] {
+                Spring::Damaged => 0,
+                _ => self.counts[i + 1][g],
+            };
+
+            if index < operational {
+                arrangement.push(Spring::Operational);
+                i += 1;
+                continue;
+            }
+
+            index -= operational;
+
+            let end = i + self.groups[g];
+            arrangement.resize(end, Spring::Damaged);
+
+            if end < length {
+                arrangement.push(Spring::Operational);
+            }
+
+            i = (end + 1).min(length);
+            g += 1;
+        }
+
+        Some(arrangement)
+    }
+
+    /// Iterates over every arrangement
+    pub fn iter(&self) -> impl Iterator<Item = Vec<Spring>> + '_ {
+        (0..self.count()).map_while(|index| self.nth(index))
+    }
+
+    /// Picks an arrangement uniformly at random
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<Spring>> {
+        let count = self.count();
+
+        (count > 0)
+            .then(|| rng.random_range(0..count))
+            .and_then(|index| self.nth(index))
+    }
 }
 
 /// Calls the parse function for each spring record
@@ -138,3 +279,340 @@ pub fn parse_record(input: &str) -> Result<Record> {
 
     Ok((springs, damaged_groups))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{HashMap, HashSet};
+
+    use itertools::Itertools;
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// Builds a concrete arrangement, derives its groups and then hides some springs,
+    /// so every generated record has at least one valid arrangement
+    fn record() -> impl Strategy<Value = Record> {
+        prop::collection::vec((any::<bool>(), any::<bool>()), 1..14)
+            .prop_map(|cells| {
+                let arrangement = cells
+                    .iter()
+                    .map(|&(damaged, _)| {
+                        if damaged {
+                            Spring::Damaged
+                        } else {
+                            Spring::Operational
+                        }
+                    })
+                    .collect_vec();
+
+                let groups = damaged_groups(&arrangement);
+
+                let springs = arrangement
+                    .into_iter()
+                    .zip(cells)
+                    .map(|(spring, (_, hidden))| if hidden { Spring::Unknown } else { spring })
+                    .collect();
+
+                (springs, groups)
+            })
+            .prop_filter("Record must have damaged groups", |(_, groups)| {
+                !groups.is_empty()
+            })
+    }
+
+    fn record_input((springs, groups): &Record) -> String {
+        let springs = springs
+            .iter()
+            .map(|s| match s {
+                Spring::Operational => '.',
+                Spring::Damaged => '#',
+                Spring::Unknown => '?',
+            })
+            .collect::<String>();
+
+        format!("{springs} {}", groups.iter().join(","))
+    }
+
+    fn damaged_groups(springs: &[Spring]) -> Vec<u32> {
+        springs
+            .split(|&s| s != Spring::Damaged)
+            .filter(|group| !group.is_empty())
+            .map(|group| u32::try_from(group.len()).expect("Group must fit in u32"))
+            .collect()
+    }
+
+    fn naive_posibilities((springs, groups): &Record) -> u64 {
+        let unknowns = springs
+            .iter()
+            .positions(|&s| s == Spring::Unknown)
+            .collect_vec();
+
+        let valid = (0..1_u32 << unknowns.len())
+            .filter(|mask| {
+                let mut springs = springs.clone();
+                for (bit, &i) in unknowns.iter().enumerate() {
+                    springs[i] = if mask >> bit & 1 == 1 {
+                        Spring::Damaged
+                    } else {
+                        Spring::Operational
+                    };
+                }
+                damaged_groups(&springs) == *groups
+            })
+            .count();
+
+        u64::try_from(valid).expect("Count must fit in u64")
+    }
+
+    /// The recursive count, kept as an oracle for `spring_arrangements`
+    fn recursive_posibilities(record: Record) -> u64 {
+        let mut cache = HashMap::new();
+
+        unknown_spring_posibilities_rec(Box::from(record.0), &record.1, 0, &mut cache)
+    }
+
+    type CacheData<'a> = (Box<[Spring]>, &'a [u32], u32);
+
+    fn unknown_spring_posibilities_rec<'a>(
+        springs: Box<[Spring]>,
+        damaged_groups: &'a [u32],
+        damaged_count: u32,
+        cache: &mut HashMap<CacheData<'a>, u64>,
+    ) -> u64 {
+        let cache_data = (springs, damaged_groups, damaged_count);
+
+        cache.get(&cache_data).copied().unwrap_or_else(|| {
+            let (springs, damaged_groups, damaged_count) = cache_data;
+            let result = match springs.split_first() {
+                Some((Spring::Operational, springs)) => {
+                    let springs = Box::from(springs);
+                    if damaged_groups.first() == Some(&damaged_count) {
+                        unknown_spring_posibilities_rec(springs, &damaged_groups[1..], 0, cache)
+                    } else if damaged_count == 0 {
+                        unknown_spring_posibilities_rec(springs, damaged_groups, 0, cache)
+                    } else {
+                        0
+                    }
+                }
+                Some((Spring::Damaged, springs)) => match damaged_groups.first() {
+                    None => 0,
+                    Some(&group) if group == damaged_count => 0,
+                    _ => unknown_spring_posibilities_rec(
+                        Box::from(springs),
+                        damaged_groups,
+                        damaged_count + 1,
+                        cache,
+                    ),
+                },
+                Some((Spring::Unknown, _)) => {
+                    // Divide into damaged and operational posibilities
+                    let mut springs_clone = springs.to_vec();
+
+                    springs_clone[0] = Spring::Damaged;
+                    let damaged_posibilities = unknown_spring_posibilities_rec(
+                        Box::from(springs_clone),
+                        damaged_groups,
+                        damaged_count,
+                        cache,
+                    );
+
+                    let mut springs_clone = springs.to_vec();
+                    springs_clone[0] = Spring::Operational;
+
+                    let operational_posibilities = unknown_spring_posibilities_rec(
+                        Box::from(springs_clone),
+                        damaged_groups,
+                        damaged_count,
+                        cache,
+                    );
+
+                    damaged_posibilities + operational_posibilities
+                }
+                None => u64::from(
+                    damaged_groups.is_empty()
+                        || (damaged_groups.len() == 1 && damaged_groups[0] == damaged_count),
+                ),
+            };
+
+            cache.insert((springs, damaged_groups, damaged_count), result);
+            result
+        })
+    }
+
+    fn is_arrangement_of(arrangement: &[Spring], (springs, groups): &Record) -> bool {
+        arrangement.len() == springs.len()
+            && arrangement
+                .iter()
+                .zip(springs)
+                .all(|(a, s)| *s == Spring::Unknown || a == s)
+            && damaged_groups(arrangement) == *groups
+    }
+
+    #[rstest::rstest]
+    #[case("???.### 1,1,3", 1, 1)]
+    #[case("???.### 1,1,3", 5, 1)]
+    #[case(".??..??...?##. 1,1,3", 5, 16_384)]
+    #[case("?###???????? 3,2,1", 5, 506_250)]
+    #[case("?###???????? 3,2,1", 0, 1)]
+    #[case("???.### 1,1,3", 5_000, 1)]
+    fn test_spring_arrangements(
+        #[case] input: &str,
+        #[case] times: usize,
+        #[case] expected: u128,
+    ) -> Result<()> {
+        let (springs, groups) = parse_record(input)?;
+
+        assert_eq!(
+            Some(expected),
+            spring_arrangements(&springs, &groups, times)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spring_arrangements_overflow() -> Result<()> {
+        let (springs, groups) = parse_record("?###???????? 3,2,1")?;
+
+        // NOTE: each extra copy multiplies the arrangements by 15
+        for times in 1..=32 {
+            let expected = 10 * 15_u128.pow(u32::try_from(times - 1)?);
+
+            assert_eq!(
+                Some(expected),
+                spring_arrangements(&springs, &groups, times)
+            );
+        }
+
+        assert_eq!(
+            Some(291_929_260_253_906_250),
+            spring_arrangements(&springs, &groups, 15)
+        );
+        assert_eq!(None, spring_arrangements(&springs, &groups, 33));
+        assert_eq!(None, spring_arrangements(&springs, &groups, 100));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrangements_example() -> Result<()> {
+        let record = parse_record("?###???????? 3,2,1")?;
+
+        let arrangements = Arrangements::new(&record).context("Arrangements overflow")?;
+        let all = arrangements.iter().collect_vec();
+
+        assert_eq!(10, arrangements.count());
+        assert_eq!(10, all.len());
+        assert_eq!(parse_record(".###....##.# 3,2,1")?.0, all[0]);
+        assert_eq!(parse_record(".###.##.#... 3,2,1")?.0, all[9]);
+        assert_eq!(None, arrangements.nth(10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrangements_impossible() -> Result<()> {
+        let arrangements =
+            Arrangements::new(&parse_record("#.# 2")?).context("Arrangements overflow")?;
+        let mut rng = XorShiftRng::seed_from_u64(0);
+
+        assert_eq!(0, arrangements.count());
+        assert_eq!(0, arrangements.iter().count());
+        assert_eq!(None, arrangements.sample(&mut rng));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample_among_billions() -> Result<()> {
+        let record = repeat_record(parse_record("?###???????? 3,2,1")?, 10);
+        let arrangements = Arrangements::new(&record).context("Arrangements overflow")?;
+        let mut rng = XorShiftRng::seed_from_u64(12);
+
+        assert_eq!(
+            unknown_spring_posibilities(&record),
+            Some(arrangements.count())
+        );
+        assert!(arrangements.count() > 1_000_000_000);
+
+        for _ in 0..100 {
+            let sample = arrangements
+                .sample(&mut rng)
+                .expect("There are arrangements");
+
+            assert!(is_arrangement_of(&sample, &record));
+        }
+
+        let last = arrangements
+            .nth(arrangements.count() - 1)
+            .expect("Last exists");
+
+        assert!(is_arrangement_of(&last, &record));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrangements_overflow() -> Result<()> {
+        let record = parse_record("?###???????? 3,2,1")?;
+
+        assert!(Arrangements::new(&repeat_record(record.clone(), 15)).is_some());
+        assert!(Arrangements::new(&repeat_record(record, 33)).is_none());
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_arrangements_match_naive_enumeration(record in record()) {
+            let arrangements = Arrangements::new(&record).expect("Generated record fits");
+            let all = arrangements.iter().collect_vec();
+
+            prop_assert_eq!(u128::from(naive_posibilities(&record)), arrangements.count());
+            prop_assert_eq!(all.len(), all.iter().collect::<HashSet<_>>().len());
+            prop_assert!(all.iter().all(|arrangement| is_arrangement_of(arrangement, &record)));
+            prop_assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        #[test]
+        fn test_sample_is_an_arrangement(record in record(), seed in any::<u64>()) {
+            let arrangements = Arrangements::new(&record).expect("Generated record fits");
+            let mut rng = XorShiftRng::seed_from_u64(seed);
+
+            let sample = arrangements.sample(&mut rng).expect("Generated record has an arrangement");
+
+            prop_assert!(is_arrangement_of(&sample, &record));
+        }
+
+        #[test]
+        fn test_parse_record(record in record()) {
+            let parsed = parse_record(&record_input(&record)).expect("Generated record is valid");
+
+            prop_assert_eq!(record, parsed);
+        }
+
+        #[test]
+        fn test_posibilities_match_naive_enumeration(record in record()) {
+            let expected = naive_posibilities(&record);
+
+            prop_assert!(expected >= 1);
+            prop_assert_eq!(Some(u128::from(expected)), unknown_spring_posibilities(&record));
+        }
+
+        #[test]
+        fn test_iterative_matches_recursive(record in record(), times in 0..4_usize) {
+            let iterative = spring_arrangements(&record.0, &record.1, times);
+
+            prop_assert_eq!(Some(u128::from(recursive_posibilities(repeat_record(record, times)))), iterative);
+        }
+
+        #[test]
+        fn test_repeat_once_is_identity(record in record()) {
+            let repeated = repeat_record(record.clone(), 1);
+
+            prop_assert_eq!(unknown_spring_posibilities(&record), unknown_spring_posibilities(&repeated));
+        }
+    }
+}
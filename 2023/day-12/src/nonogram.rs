@@ -0,0 +1,379 @@
+//! Nonograms, where every row and column is a spring record whose damaged
+//! groups are the clues and whose damaged springs are the filled cells
+
+use std::fmt::{self, Display, Write};
+
+use anyhow::{ensure, Context, Result};
+use itertools::Itertools;
+
+use crate::{spring_arrangements, Spring};
+
+/// A grid of cells, unknown until solved
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid(Vec<Vec<Spring>>);
+
+impl Grid {
+    #[must_use]
+    pub fn new(height: usize, width: usize) -> Self {
+        Self(vec![vec![Spring::Unknown; width]; height])
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> &[Vec<Spring>] {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn column(&self, x: usize) -> Vec<Spring> {
+        self.0.iter().map(|row| row[x]).collect()
+    }
+
+    #[must_use]
+    pub fn is_solved(&self) -> bool {
+        self.0.iter().flatten().all(|&cell| cell != Spring::Unknown)
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.0 {
+            for cell in row {
+                f.write_char(match cell {
+                    Spring::Operational => '.',
+                    Spring::Damaged => '#',
+                    Spring::Unknown => '?',
+                })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of solving a nonogram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Grid),
+    /// Two of the grids that satisfy the clues
+    Ambiguous(Grid, Grid),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nonogram {
+    rows: Vec<Vec<u32>>,
+    columns: Vec<Vec<u32>>,
+}
+
+impl Nonogram {
+    /// Creates a nonogram from the clues of each row and column
+    ///
+    /// # Errors
+    /// If a clue does not fit in its line or rows and columns fill a
+    /// different number of cells
+    pub fn new(rows: Vec<Vec<u32>>, columns: Vec<Vec<u32>>) -> Result<Self> {
+        ensure!(
+            !rows.is_empty() && !columns.is_empty(),
+            "Nonogram must have rows and columns"
+        );
+
+        for (clues, length) in rows
+            .iter()
+            .map(|clues| (clues, columns.len()))
+            .chain(columns.iter().map(|clues| (clues, rows.len())))
+        {
+            let needed = clues.iter().map(|&clue| clue as usize).sum::<usize>() + clues.len();
+
+            ensure!(
+                clues.iter().all(|&clue| clue > 0) && needed <= length + 1,
+                "Clues {clues:?} do not fit in {length} cells"
+            );
+        }
+
+        let filled = |lines: &[Vec<u32>]| lines.iter().flatten().sum::<u32>();
+
+        ensure!(
+            filled(&rows) == filled(&columns),
+            "Rows and columns must fill the same number of cells"
+        );
+
+        Ok(Self { rows, columns })
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> &[Vec<u32>] {
+        &self.rows
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> &[Vec<u32>] {
+        &self.columns
+    }
+
+    /// Solves the nonogram, or `None` if no grid satisfies the clues
+    #[must_use]
+    pub fn solve(&self) -> Option<Solution> {
+        let mut solutions = Vec::new();
+
+        self.search(
+            Grid::new(self.rows.len(), self.columns.len()),
+            &mut solutions,
+        );
+
+        let mut solutions = solutions.into_iter();
+
+        match (solutions.next(), solutions.next()) {
+            (Some(first), Some(second)) => Some(Solution::Ambiguous(first, second)),
+            (Some(grid), None) => Some(Solution::Unique(grid)),
+            _ => None,
+        }
+    }
+
+    /// Finds up to two solutions, line solving as far as it goes and then
+    /// guessing the first unknown cell
+    fn search(&self, mut grid: Grid, solutions: &mut Vec<Grid>) {
+        if solutions.len() >= 2 || self.propagate(&mut grid).is_none() {
+            return;
+        }
+
+        let Some((y, x)) = grid.0.iter().enumerate().find_map(|(y, row)| {
+            row.iter()
+                .position(|&cell| cell == Spring::Unknown)
+                .map(|x| (y, x))
+        }) else {
+            solutions.push(grid);
+            return;
+        };
+
+        for guess in [Spring::Damaged, Spring::Operational] {
+            let mut grid = grid.clone();
+            grid.0[y][x] = guess;
+
+            self.search(grid, solutions);
+        }
+    }
+
+    /// Solves lines until nothing changes, `None` if a line has no arrangement
+    fn propagate(&self, grid: &mut Grid) -> Option<()> {
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (y, clues) in self.rows.iter().enumerate() {
+                let line = solve_line(grid.0[y].clone(), clues)?;
+
+                changed |= line != grid.0[y];
+                grid.0[y] = line;
+            }
+
+            for (x, clues) in self.columns.iter().enumerate() {
+                let line = solve_line(grid.column(x), clues)?;
+
+                for (row, cell) in grid.0.iter_mut().zip(line) {
+                    changed |= row[x] != cell;
+                    row[x] = cell;
+                }
+            }
+        }
+
+        Some(())
+    }
+}
+
+/// Fixes every unknown cell of a line that is the same in all its
+/// arrangements, `None` if there are none
+#[must_use]
+pub fn solve_line(mut line: Vec<Spring>, clues: &[u32]) -> Option<Vec<Spring>> {
+    // NOTE: an overflowing count still has arrangements
+    let possible = |line: &[Spring]| spring_arrangements(line, clues, 1) != Some(0);
+
+    if !possible(&line) {
+        return None;
+    }
+
+    for i in 0..line.len() {
+        if line[i] != Spring::Unknown {
+            continue;
+        }
+
+        line[i] = Spring::Damaged;
+        let damaged = possible(&line);
+
+        line[i] = Spring::Operational;
+        let operational = possible(&line);
+
+        line[i] = match (damaged, operational) {
+            (_, false) => Spring::Damaged,
+            (false, _) => Spring::Operational,
+            _ => Spring::Unknown,
+        };
+    }
+
+    Some(line)
+}
+
+impl Display for Nonogram {
+    /// Writes the row clues and then the column clues, separated by an empty
+    /// line, with a line without clues written as `0`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let section = |lines: &[Vec<u32>]| {
+            lines
+                .iter()
+                .map(|clues| {
+                    if clues.is_empty() {
+                        String::from("0")
+                    } else {
+                        clues.iter().join(",")
+                    }
+                })
+                .join("\n")
+        };
+
+        writeln!(f, "{}\n\n{}", section(&self.rows), section(&self.columns))
+    }
+}
+
+/// Parses a nonogram as written by its `Display`
+///
+/// # Errors
+/// If the input is not a valid nonogram
+pub fn parse_nonogram(input: &str) -> Result<Nonogram> {
+    let (rows, columns) = input
+        .trim_end()
+        .split_once("\n\n")
+        .context("Rows and columns must be separated by an empty line")?;
+
+    let section = |section: &str| {
+        section
+            .lines()
+            .map(|line| {
+                line.split(',')
+                    .map(|clue| Ok(clue.trim().parse::<u32>()?))
+                    .filter_ok(|&clue| clue > 0)
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+
+    Nonogram::new(section(rows)?, section(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    const HEART: &str = "1,1
+5
+5
+3
+1
+
+2
+4
+4
+4
+2
+";
+
+    #[test]
+    fn test_solve_unique() -> Result<()> {
+        let nonogram = parse_nonogram(HEART)?;
+
+        let Some(Solution::Unique(grid)) = nonogram.solve() else {
+            panic!("Heart has a unique solution");
+        };
+
+        assert_eq!(
+            "\
+.#.#.
+#####
+#####
+.###.
+..#..
+",
+            grid.to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_ambiguous() -> Result<()> {
+        let nonogram = parse_nonogram("1\n1\n\n1\n1")?;
+
+        let Some(Solution::Ambiguous(first, second)) = nonogram.solve() else {
+            panic!("Diagonals are both solutions");
+        };
+
+        assert_eq!("#.\n.#\n", first.to_string());
+        assert_eq!(".#\n#.\n", second.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_needs_backtracking() -> Result<()> {
+        // NOTE: the last row could be `#.#.`, `#..#` or `.#.#` and no column
+        // narrows it down until a cell is guessed
+        let nonogram = parse_nonogram("0\n0\n2\n1,1\n\n1\n1\n1\n1")?;
+        let mut grid = Grid::new(4, 4);
+
+        assert_eq!(Some(()), nonogram.propagate(&mut grid));
+        assert!(!grid.is_solved());
+
+        let Some(Solution::Unique(grid)) = nonogram.solve() else {
+            panic!("Puzzle has a unique solution");
+        };
+
+        assert_eq!("....\n....\n.##.\n#..#\n", grid.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsolvable() -> Result<()> {
+        let nonogram = parse_nonogram("1,1\n0\n1,1\n\n1\n2\n1")?;
+
+        assert_eq!(None, nonogram.solve());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_line() -> Result<()> {
+        let (line, _) = crate::parse_record("?????????? 8")?;
+
+        let line = solve_line(line, &[8]).context("Line has arrangements")?;
+
+        assert_eq!("??######??\n", Grid(vec![line]).to_string());
+        assert_eq!(None, solve_line(vec![Spring::Unknown; 3], &[4]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_parse() -> Result<()> {
+        let nonogram = parse_nonogram(HEART)?;
+
+        assert_eq!(HEART, nonogram.to_string());
+        assert_eq!(nonogram, parse_nonogram(&nonogram.to_string())?);
+
+        let empty = Nonogram::new(vec![vec![], vec![1]], vec![vec![1], vec![]])?;
+
+        assert_eq!("0\n1\n\n1\n0\n", empty.to_string());
+        assert_eq!(empty, parse_nonogram(&empty.to_string())?);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("1\n1")]
+    #[case("3\n\n1\n1")]
+    #[case("1\n\n1\n1")]
+    #[case("x\n\n1")]
+    fn test_invalid(#[case] input: &str) {
+        assert!(parse_nonogram(input).is_err());
+    }
+}
@@ -1,74 +1,41 @@
 use anyhow::Result;
-use day_13::{parse_environment, MirrorAccessor};
-use itertools::Itertools;
+use day_13::parse_environment;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
     Ok(())
 }
 
-enum Direction {
-    Vertical(usize),
-    Horizontal(usize),
-}
-
 #[inline]
 fn process(input: &str) -> Result<usize> {
     let mirrors = parse_environment(input)?;
+    timing::parsed();
 
     let result = mirrors
-        .into_iter()
-        .filter_map(|m| find_mirror(&m.rows()).or_else(|| find_mirror(&m.columns())))
-        .fold(0, |sum, idx| match idx {
-            Direction::Vertical(idx) => sum + idx,
-            Direction::Horizontal(idx) => sum + 100 * idx,
-        });
+        .iter()
+        .filter_map(|m| m.exact_reflections(1).first().map(|r| r.line.summary()))
+        .sum();
 
     Ok(result)
 }
 
-fn find_mirror(mirror: &MirrorAccessor) -> Option<Direction> {
-    mirror
-        .lines()
-        .enumerate()
-        .tuple_windows()
-        .filter_map(|((idx1, v1), (_, v2))| {
-            let difference = differences(v1, v2);
-            (difference <= 1).then_some((idx1 + 1, difference))
-        })
-        .find_map(|(idx, mut difference)| {
-            difference += (0..idx - 1)
-                .rev()
-                .zip(idx + 1..mirror.len())
-                .map(|(id1, id2)| differences(mirror.nth_line(id1), mirror.nth_line(id2)))
-                .sum::<usize>();
-
-            (difference == 1).then_some(match mirror {
-                MirrorAccessor::Rows(_) => Direction::Horizontal(idx),
-                MirrorAccessor::Columns(_) => Direction::Vertical(idx),
-            })
-        })
-}
-
-#[inline]
-fn differences<E: Eq, I>(v1: I, v2: I) -> usize
-where
-    I: IntoIterator<Item = E>,
-{
-    v1.into_iter().zip(v2).filter(|(e1, e2)| e1 != e2).count()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "#.##..##.
 ..#.##.#.
 ##......#
@@ -85,8 +52,10 @@ mod tests {
 ..##..###
 #....#..#";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(400, result);
+
+        Ok(())
     }
 }
@@ -1,4 +1,6 @@
-use anyhow::{bail, Result};
+use std::fmt::{self, Display, Write};
+
+use anyhow::{bail, ensure, Context, Result};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Element {
@@ -19,6 +21,44 @@ impl TryFrom<char> for Element {
     }
 }
 
+impl Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(match self {
+            Self::Ash => '.',
+            Self::Rock => '#',
+        })
+    }
+}
+
+/// A cell of a pattern as `(row, column)`
+pub type Cell = (usize, usize);
+
+/// A line of reflection, after a number of rows or columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    Horizontal(usize),
+    Vertical(usize),
+}
+
+impl Line {
+    /// Columns left of a vertical line, or 100 times the rows above a
+    /// horizontal one
+    #[must_use]
+    pub const fn summary(self) -> usize {
+        match self {
+            Self::Horizontal(rows) => 100 * rows,
+            Self::Vertical(columns) => columns,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    pub line: Line,
+    /// Pairs of cells mirrored by the line that differ, the smudges
+    pub smudges: Vec<[Cell; 2]>,
+}
+
 #[derive(Debug)]
 pub struct Mirror {
     matrix: Vec<Vec<Element>>,
@@ -27,31 +67,141 @@ pub struct Mirror {
 }
 
 impl Mirror {
-    /// # Panics
-    /// if all rows dont have the same length
-    #[must_use]
-    pub fn new(matrix: Vec<Vec<Element>>) -> Self {
+    /// # Errors
+    /// if the matrix is empty or all rows dont have the same length
+    pub fn new(matrix: Vec<Vec<Element>>) -> Result<Self> {
         let rows = matrix.len();
-        let columns = matrix[0].len();
+        let columns = matrix.first().context("Mirror must not be empty")?.len();
 
-        matrix.iter().for_each(|v| assert_eq!(columns, v.len()));
+        ensure!(
+            matrix.iter().all(|v| v.len() == columns),
+            "All mirror rows must have the same length"
+        );
 
-        Self {
+        Ok(Self {
             matrix,
             rows,
             columns,
-        }
+        })
     }
 
     #[must_use]
-    pub const fn rows(&self) -> MirrorAccessor {
+    pub const fn rows(&self) -> MirrorAccessor<'_> {
         MirrorAccessor::Rows(self)
     }
 
     #[must_use]
-    pub const fn columns(&self) -> MirrorAccessor {
+    pub const fn columns(&self) -> MirrorAccessor<'_> {
         MirrorAccessor::Columns(self)
     }
+
+    /// Finds every line of reflection with at most `max_smudges` differing
+    /// pairs of cells, horizontal lines first
+    #[must_use]
+    pub fn reflections(&self, max_smudges: usize) -> Vec<Reflection> {
+        let horizontal = (1..self.rows).filter_map(|rows| {
+            let pairs = (0..rows)
+                .rev()
+                .zip(rows..self.rows)
+                .flat_map(|(above, below)| {
+                    (0..self.columns).map(move |column| [(above, column), (below, column)])
+                });
+
+            self.reflection(Line::Horizontal(rows), pairs, max_smudges)
+        });
+
+        let vertical = (1..self.columns).filter_map(|columns| {
+            let pairs = (0..columns)
+                .rev()
+                .zip(columns..self.columns)
+                .flat_map(|(left, right)| {
+                    (0..self.rows).map(move |row| [(row, left), (row, right)])
+                });
+
+            self.reflection(Line::Vertical(columns), pairs, max_smudges)
+        });
+
+        horizontal.chain(vertical).collect()
+    }
+
+    /// Finds every line of reflection with exactly `smudges` differing pairs
+    /// of cells
+    #[must_use]
+    pub fn exact_reflections(&self, smudges: usize) -> Vec<Reflection> {
+        self.reflections(smudges)
+            .into_iter()
+            .filter(|reflection| reflection.smudges.len() == smudges)
+            .collect()
+    }
+
+    fn reflection(
+        &self,
+        line: Line,
+        pairs: impl Iterator<Item = [Cell; 2]>,
+        max_smudges: usize,
+    ) -> Option<Reflection> {
+        let mut smudges = Vec::new();
+
+        for [a, b] in pairs {
+            if self.matrix[a.0][a.1] != self.matrix[b.0][b.1] {
+                if smudges.len() == max_smudges {
+                    return None;
+                }
+
+                smudges.push([a, b]);
+            }
+        }
+
+        Some(Reflection { line, smudges })
+    }
+
+    /// Folds the pattern along a line onto its larger side, drawing a `?`
+    /// where a cell and its reflection differ
+    #[must_use]
+    pub fn fold(&self, line: Line) -> String {
+        let (rows, columns) = match line {
+            Line::Horizontal(at) if 2 * at >= self.rows => (0..at, 0..self.columns),
+            Line::Horizontal(at) => (at..self.rows, 0..self.columns),
+            Line::Vertical(at) if 2 * at >= self.columns => (0..self.rows, 0..at),
+            Line::Vertical(at) => (0..self.rows, at..self.columns),
+        };
+
+        let reflect = |(row, column): Cell| match line {
+            Line::Horizontal(at) => (2 * at).checked_sub(row + 1).map(|row| (row, column)),
+            Line::Vertical(at) => (2 * at).checked_sub(column + 1).map(|column| (row, column)),
+        };
+
+        let mut folded = String::new();
+
+        for row in rows {
+            for column in columns.clone() {
+                let element = self.matrix[row][column];
+                let reflected = reflect((row, column))
+                    .and_then(|(row, column)| self.matrix.get(row)?.get(column));
+
+                match reflected {
+                    Some(&reflected) if reflected != element => folded.push('?'),
+                    _ => folded.push_str(&element.to_string()),
+                }
+            }
+
+            folded.push('\n');
+        }
+
+        folded
+    }
+}
+
+impl Display for Mirror {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.matrix {
+            for element in row {
+                write!(f, "{element}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
 }
 
 pub enum MirrorAccessor<'a> {
@@ -59,7 +209,7 @@ pub enum MirrorAccessor<'a> {
     Columns(&'a Mirror),
 }
 
-impl<'a> MirrorAccessor<'a> {
+impl MirrorAccessor<'_> {
     #[must_use]
     pub const fn len(&self) -> usize {
         match self {
@@ -109,5 +259,205 @@ fn parse_mirror(input: &str) -> Result<Mirror> {
         })
         .collect::<Result<Vec<Vec<Element>>>>()?;
 
-    Ok(Mirror::new(matrix))
+    Mirror::new(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn test_reflections() -> Result<()> {
+        let mirrors = parse_environment(EXAMPLE)?;
+
+        assert_eq!(
+            vec![Reflection {
+                line: Line::Vertical(5),
+                smudges: vec![],
+            }],
+            mirrors[0].reflections(0)
+        );
+        assert_eq!(
+            vec![
+                Reflection {
+                    line: Line::Horizontal(3),
+                    smudges: vec![[(0, 0), (5, 0)]],
+                },
+                Reflection {
+                    line: Line::Vertical(5),
+                    smudges: vec![],
+                },
+            ],
+            mirrors[0].reflections(1)
+        );
+        assert_eq!(
+            vec![Reflection {
+                line: Line::Horizontal(1),
+                smudges: vec![[(0, 4), (1, 4)]],
+            }],
+            mirrors[1].exact_reflections(1)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exactly_and_at_most() -> Result<()> {
+        let mirrors = parse_environment(EXAMPLE)?;
+
+        for mirror in &mirrors {
+            for smudges in 0..4 {
+                let at_most = mirror.reflections(smudges);
+                let exactly = mirror.exact_reflections(smudges);
+
+                assert!(at_most.iter().all(|r| r.smudges.len() <= smudges));
+                assert!(exactly.iter().all(|r| r.smudges.len() == smudges));
+                assert!(exactly.iter().all(|r| at_most.contains(r)));
+            }
+        }
+
+        let lines = mirrors[1].reflections(usize::MAX).len();
+
+        assert_eq!(mirrors[1].rows - 1 + mirrors[1].columns - 1, lines);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold() -> Result<()> {
+        let mirrors = parse_environment(EXAMPLE)?;
+
+        assert_eq!(
+            "##......#\n..#.##.#.\n?.##..##.\n#.#.##.#.\n",
+            mirrors[0].fold(Line::Horizontal(3))
+        );
+        assert_eq!(
+            "#.##.\n..#.#\n##...\n##...\n..#.#\n..##.\n#.#.#\n",
+            mirrors[0].fold(Line::Vertical(5))
+        );
+        assert_eq!(
+            "#...##..#\n#....#..#\n..##..###\n#####.##.\n",
+            mirrors[1].fold(Line::Horizontal(4))
+        );
+
+        Ok(())
+    }
+
+    /// Generates a small pattern of ash and rocks
+    fn pattern() -> impl Strategy<Value = Vec<Vec<Element>>> {
+        let element = prop_oneof![Just(Element::Ash), Just(Element::Rock)];
+
+        grids::grid(element, 1..10, 1..10)
+    }
+
+    /// Generates a pattern mirrored below its first `rows` rows, with part of
+    /// the mirrored copy cut off
+    fn mirrored_pattern() -> impl Strategy<Value = (Vec<Vec<Element>>, usize)> {
+        pattern().prop_flat_map(|pattern| {
+            let rows = pattern.len();
+
+            (0..=rows).prop_map(move |kept| {
+                let mirrored = pattern.iter().rev().take(kept).cloned();
+
+                (pattern.iter().cloned().chain(mirrored).collect(), rows)
+            })
+        })
+    }
+
+    /// Counts the differing pairs of lines mirrored after `at` lines
+    fn naive_smudges(lines: &MirrorAccessor, at: usize) -> usize {
+        (0..at)
+            .rev()
+            .zip(at..lines.len())
+            .map(|(a, b)| {
+                lines
+                    .nth_line(a)
+                    .zip(lines.nth_line(b))
+                    .filter(|(a, b)| a != b)
+                    .count()
+            })
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_generated_pattern(pattern in pattern()) {
+            let mirror = Mirror::new(pattern).expect("Generated pattern is valid");
+            let input = mirror.to_string();
+
+            let parsed = parse_environment(input.trim_end()).expect("Displayed pattern is valid");
+
+            prop_assert_eq!(1, parsed.len());
+            prop_assert_eq!(input, parsed[0].to_string());
+        }
+
+        #[test]
+        fn test_reflections_match_naive(pattern in pattern(), max_smudges in 0..4_usize) {
+            let mirror = Mirror::new(pattern).expect("Generated pattern is valid");
+
+            let horizontal = (1..mirror.rows)
+                .map(|at| (Line::Horizontal(at), naive_smudges(&mirror.rows(), at)));
+            let vertical = (1..mirror.columns)
+                .map(|at| (Line::Vertical(at), naive_smudges(&mirror.columns(), at)));
+
+            let expected = horizontal
+                .chain(vertical)
+                .filter(|&(_, smudges)| smudges <= max_smudges)
+                .collect::<Vec<_>>();
+
+            let reflections = mirror
+                .reflections(max_smudges)
+                .into_iter()
+                .map(|r| (r.line, r.smudges.len()))
+                .collect::<Vec<_>>();
+
+            prop_assert_eq!(expected, reflections);
+        }
+
+        #[test]
+        fn test_mirrored_pattern_reflects((pattern, rows) in mirrored_pattern()) {
+            let mirror = Mirror::new(pattern.clone()).expect("Generated pattern is valid");
+            let line = Line::Horizontal(rows);
+
+            if rows < mirror.rows {
+                prop_assert!(mirror.exact_reflections(0).iter().any(|r| r.line == line));
+            }
+
+            // NOTE: flipping the last row flips a mirrored cell whenever the
+            // copy was kept
+            let mut smudged = pattern;
+            let last = smudged.len() - 1;
+            smudged[last][0] = match smudged[last][0] {
+                Element::Ash => Element::Rock,
+                Element::Rock => Element::Ash,
+            };
+
+            let smudged = Mirror::new(smudged).expect("Smudged pattern is valid");
+
+            if rows < smudged.rows {
+                let reflection = smudged.exact_reflections(1).into_iter().find(|r| r.line == line);
+                let expected = [(2 * rows - 1 - last, 0), (last, 0)];
+
+                prop_assert_eq!(Some(vec![expected]), reflection.map(|r| r.smudges));
+            }
+        }
+    }
 }
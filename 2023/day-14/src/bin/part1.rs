@@ -1,10 +1,16 @@
 use anyhow::Result;
-use day_14::Rock;
+use day_14::Direction;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -14,18 +20,11 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let mut platform = day_14::parse_platform(input)?;
+    timing::parsed();
 
-    day_14::slide_platform_north(&mut platform);
-
-    let result = platform
-        .iter()
-        .map(|(c, r)| match r {
-            Rock::Rounded => platform.height as u64 - c.y,
-            Rock::Cube => 0,
-        })
-        .sum();
+    platform.tilt(Direction::North);
 
-    Ok(result)
+    Ok(platform.load(Direction::North))
 }
 
 #[cfg(test)]
@@ -33,7 +32,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -45,8 +44,10 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(136, result);
+
+        Ok(())
     }
 }
@@ -1,12 +1,18 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 use anyhow::Result;
-use day_14::{Platform, Rock};
+use day_14::{Direction, SPIN_CYCLE};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -18,48 +24,25 @@ const CYCLES: u64 = 1_000_000_000;
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let mut platform = day_14::parse_platform(input)?;
+    timing::parsed();
 
-    let start_to_loop_size = slide_until_loop(&mut platform);
-
-    let loop_size = slide_until_loop(&mut platform);
-
-    let missing_cycles = (CYCLES - start_to_loop_size) % loop_size;
-
-    for _ in 0..missing_cycles {
-        day_14::slide_platform_cycle(&mut platform);
-    }
-
-    let result = platform
-        .iter()
-        .map(|(c, r)| match r {
-            Rock::Rounded => platform.height as u64 - c.y,
-            Rock::Cube => 0,
-        })
-        .sum();
-
-    Ok(result)
-}
-
-fn slide_until_loop(platform: &mut Platform) -> u64 {
-    let mut past_platforms = HashSet::new();
-
-    past_platforms.insert(platform.to_string());
+    let mut seen = HashMap::new();
 
-    let mut idx = 1;
+    for cycle in 0..CYCLES {
+        if let Some(start) = seen.insert(platform.rounded().to_vec(), cycle) {
+            let missing_cycles = (CYCLES - cycle) % (cycle - start);
 
-    loop {
-        day_14::slide_platform_cycle(platform);
+            for _ in 0..missing_cycles {
+                platform.tilt_sequence(&SPIN_CYCLE);
+            }
 
-        let string = platform.to_string();
-
-        if past_platforms.contains(&string) {
-            break idx;
+            break;
         }
 
-        past_platforms.insert(string);
-
-        idx += 1;
+        platform.tilt_sequence(&SPIN_CYCLE);
     }
+
+    Ok(platform.load(Direction::North))
 }
 
 #[cfg(test)]
@@ -67,7 +50,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
+    fn test_example() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -79,13 +62,15 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let result = process(input).unwrap();
+        let result = process(input)?;
 
         assert_eq!(64, result);
+
+        Ok(())
     }
 
     #[test]
-    fn test_north() {
+    fn test_north() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -97,9 +82,9 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = day_14::parse_platform(input).unwrap();
+        let mut platform = day_14::parse_platform(input)?;
 
-        day_14::slide_platform_north(&mut platform);
+        platform.tilt(Direction::North);
 
         let result = "OOOO.#.O..
 OO..#....#
@@ -114,10 +99,12 @@ O..#.OO...
 ";
 
         assert_eq!(result, platform.to_string());
+
+        Ok(())
     }
 
     #[test]
-    fn test_west() {
+    fn test_west() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -129,9 +116,9 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = day_14::parse_platform(input).unwrap();
+        let mut platform = day_14::parse_platform(input)?;
 
-        day_14::slide_platform_west(&mut platform);
+        platform.tilt(Direction::West);
 
         let result = "O....#....
 OOO.#....#
@@ -146,10 +133,12 @@ O.........
 ";
 
         assert_eq!(result, platform.to_string());
+
+        Ok(())
     }
 
     #[test]
-    fn test_south() {
+    fn test_south() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -161,9 +150,9 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = day_14::parse_platform(input).unwrap();
+        let mut platform = day_14::parse_platform(input)?;
 
-        day_14::slide_platform_south(&mut platform);
+        platform.tilt(Direction::South);
 
         let result = ".....#....
 ....#....#
@@ -178,10 +167,12 @@ OO....OO..
 ";
 
         assert_eq!(result, platform.to_string());
+
+        Ok(())
     }
 
     #[test]
-    fn test_east() {
+    fn test_east() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -193,9 +184,9 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = day_14::parse_platform(input).unwrap();
+        let mut platform = day_14::parse_platform(input)?;
 
-        day_14::slide_platform_east(&mut platform);
+        platform.tilt(Direction::East);
 
         let result = "....O#....
 .OOO#....#
@@ -210,10 +201,12 @@ O.#..O.#.#
 ";
 
         assert_eq!(result, platform.to_string());
+
+        Ok(())
     }
 
     #[test]
-    fn test_cycles() {
+    fn test_cycles() -> Result<()> {
         let input = "O....#....
 O.OO#....#
 .....##...
@@ -225,9 +218,9 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = day_14::parse_platform(input).unwrap();
+        let mut platform = day_14::parse_platform(input)?;
 
-        day_14::slide_platform_cycle(&mut platform);
+        platform.tilt_sequence(&SPIN_CYCLE);
 
         let first = ".....#....
 ....#...O#
@@ -243,7 +236,7 @@ O.#..O.#.#
 
         assert_eq!(first, platform.to_string());
 
-        day_14::slide_platform_cycle(&mut platform);
+        platform.tilt_sequence(&SPIN_CYCLE);
 
         let second = ".....#....
 ....#...O#
@@ -259,7 +252,7 @@ O.#..O.#.#
 
         assert_eq!(second, platform.to_string());
 
-        day_14::slide_platform_cycle(&mut platform);
+        platform.tilt_sequence(&SPIN_CYCLE);
 
         let third = ".....#....
 ....#...O#
@@ -274,5 +267,7 @@ O.#..O.#.#
 ";
 
         assert_eq!(third, platform.to_string());
+
+        Ok(())
     }
 }
@@ -1,10 +1,10 @@
-use std::collections::HashMap;
 use std::fmt::{Display, Write};
-use std::ops::{Deref, DerefMut};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Rock {
     Rounded,
     Cube,
@@ -46,141 +46,355 @@ impl From<(usize, usize)> for Coordinates {
     }
 }
 
-pub struct Platform {
-    pub matrix: HashMap<Coordinates, Rock>,
-    pub length: usize,
-    pub height: usize,
+/// A way to tilt the platform, also naming the edge rocks roll towards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
 }
 
-impl Deref for Platform {
-    type Target = HashMap<Coordinates, Rock>;
+impl Direction {
+    /// Whether rocks roll along columns
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Self::North | Self::South)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.matrix
+    /// Whether rocks roll towards the first row or column
+    #[must_use]
+    pub const fn towards_start(self) -> bool {
+        matches!(self, Self::North | Self::West)
     }
 }
 
-impl DerefMut for Platform {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.matrix
+/// Tilts of a spin cycle
+pub const SPIN_CYCLE: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self> {
+        match value {
+            'N' => Ok(Self::North),
+            'W' => Ok(Self::West),
+            'S' => Ok(Self::South),
+            'E' => Ok(Self::East),
+            _ => bail!("Invalid direction {value:?}"),
+        }
     }
 }
 
-impl Display for Platform {
+impl Display for Direction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.length {
-                match self.get(&(x, y).into()) {
-                    Some(rock) => f.write_str(&rock.to_string())?,
-                    None => f.write_char('.')?,
-                }
-            }
-            f.write_char('\n')?;
+        match self {
+            Self::North => f.write_char('N'),
+            Self::West => f.write_char('W'),
+            Self::South => f.write_char('S'),
+            Self::East => f.write_char('E'),
         }
-        Ok(())
     }
 }
 
-pub fn slide_platform_cycle(platform: &mut Platform) {
-    slide_platform_north(platform);
-    slide_platform_west(platform);
-    slide_platform_south(platform);
-    slide_platform_east(platform);
+/// Parses a tilt sequence such as `NWSE`
+///
+/// # Errors
+/// If a character is not a direction
+pub fn parse_directions(input: &str) -> Result<Vec<Direction>> {
+    input.trim().chars().map(Direction::try_from).collect()
 }
 
-pub fn slide_platform_north(platform: &mut Platform) {
-    for x in 0..platform.length {
-        let mut rock_to_move = None;
-        for y in (0..platform.height).rev() {
-            match platform.get(&(x, y).into()) {
-                Some(Rock::Rounded) => {
-                    if rock_to_move.is_none() {
-                        rock_to_move = Some(y);
-                    }
+const WORD: usize = u64::BITS as usize;
+
+/// A platform stored as one bitset per row for the rounded rocks and another
+/// for the cube rocks, bit `x` of a row being column `x`
+///
+/// Rolling counts the rounded rocks between cubes along each row or column
+/// and refills that stretch from the edge they roll towards.
+#[derive(Debug, Clone)]
+pub struct Platform {
+    rounded: Vec<u64>,
+    cubes: Vec<u64>,
+    /// Stretches of each row between cube rocks
+    segments: Vec<Vec<Range<usize>>>,
+    /// Stretches of each column between cube rocks
+    column_segments: Vec<Vec<Range<usize>>>,
+    words: usize,
+    pub length: usize,
+    pub height: usize,
+}
+
+impl PartialEq for Platform {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length
+            && self.height == other.height
+            && self.rounded == other.rounded
+            && self.cubes == other.cubes
+    }
+}
+
+impl Eq for Platform {}
+
+impl Hash for Platform {
+    /// Hashes only the rounded rocks, cube rocks never move
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rounded.hash(state);
+    }
+}
+
+impl Platform {
+    /// Creates a platform from the rocks at each position
+    ///
+    /// # Errors
+    /// If a rock is outside the platform
+    pub fn new(
+        length: usize,
+        height: usize,
+        rocks: impl IntoIterator<Item = ((usize, usize), Rock)>,
+    ) -> Result<Self> {
+        let words = length.div_ceil(WORD);
+        let mut rounded = vec![0; words * height];
+        let mut cubes = vec![0; words * height];
+
+        for ((x, y), rock) in rocks {
+            ensure!(
+                x < length && y < height,
+                "Rock at ({x}, {y}) is outside the platform"
+            );
+
+            let bits = match rock {
+                Rock::Rounded => &mut rounded,
+                Rock::Cube => &mut cubes,
+            };
+
+            bits[y * words + x / WORD] |= 1 << (x % WORD);
+        }
+
+        let is_cube = |x: usize, y: usize| is_set(&cubes[y * words..(y + 1) * words], x);
+
+        let segments = (0..height)
+            .map(|y| stretches(length, |x| is_cube(x, y)))
+            .collect();
+        let column_segments = (0..length)
+            .map(|x| stretches(height, |y| is_cube(x, y)))
+            .collect();
+
+        Ok(Self {
+            rounded,
+            cubes,
+            segments,
+            column_segments,
+            words,
+            length,
+            height,
+        })
+    }
+
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<Rock> {
+        if x >= self.length || y >= self.height {
+            return None;
+        }
+
+        if is_set(self.row(&self.rounded, y), x) {
+            Some(Rock::Rounded)
+        } else if is_set(self.row(&self.cubes, y), x) {
+            Some(Rock::Cube)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over every rock and where it is
+    pub fn rocks(&self) -> impl Iterator<Item = (Coordinates, Rock)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.length).map(move |x| (x, y)))
+            .filter_map(|(x, y)| Some(((x, y).into(), self.get(x, y)?)))
+    }
+
+    /// Load on the support beams of an edge, each rounded rock weighs the
+    /// rows or columns from it to the opposite edge
+    #[must_use]
+    pub fn load(&self, edge: Direction) -> u64 {
+        let weight = |x: usize, y: usize| match edge {
+            Direction::North => self.height - y,
+            Direction::West => self.length - x,
+            Direction::South => y + 1,
+            Direction::East => x + 1,
+        };
+
+        (0..self.height)
+            .flat_map(|y| {
+                self.row(&self.rounded, y)
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(w, &word)| set_bits(word).map(move |bit| (w * WORD + bit, y)))
+            })
+            .map(|(x, y)| weight(x, y) as u64)
+            .sum()
+    }
+
+    /// Tilts the platform so every rounded rock rolls towards an edge
+    pub fn tilt(&mut self, direction: Direction) {
+        if direction.is_vertical() {
+            self.slide_vertical(direction);
+        } else {
+            self.slide_horizontal(direction);
+        }
+    }
+
+    /// Tilts the platform in each direction in turn
+    pub fn tilt_sequence<'a>(&mut self, directions: impl IntoIterator<Item = &'a Direction>) {
+        for &direction in directions {
+            self.tilt(direction);
+        }
+    }
+
+    /// Bitsets of the rounded rocks, row by row, the only part of a platform
+    /// a tilt changes
+    #[must_use]
+    pub fn rounded(&self) -> &[u64] {
+        &self.rounded
+    }
+
+    fn row<'a>(&self, bits: &'a [u64], y: usize) -> &'a [u64] {
+        &bits[y * self.words..(y + 1) * self.words]
+    }
+
+    /// Packs the rounded rocks between cube rocks towards the north or south
+    /// end of each stretch of a column
+    fn slide_vertical(&mut self, direction: Direction) {
+        for (x, segments) in self.column_segments.iter().enumerate() {
+            let (w, bit) = (x / WORD, 1 << (x % WORD));
+
+            for segment in segments {
+                let mut rocks = 0;
+
+                for y in segment.clone() {
+                    let word = &mut self.rounded[y * self.words + w];
+                    rocks += usize::from(*word & bit != 0);
+                    *word &= !bit;
                 }
-                Some(Rock::Cube) => rock_to_move = None,
-                None => {
-                    if let Some(old_rock) = rock_to_move.take() {
-                        platform.remove(&(x, old_rock).into());
-                        platform.insert((x, y).into(), Rock::Rounded);
 
-                        rock_to_move = Some(old_rock - 1);
-                    }
+                let packed = if direction.towards_start() {
+                    segment.start..segment.start + rocks
+                } else {
+                    segment.end - rocks..segment.end
+                };
+
+                for y in packed {
+                    self.rounded[y * self.words + w] |= bit;
                 }
             }
         }
     }
-}
 
-pub fn slide_platform_west(platform: &mut Platform) {
-    for y in 0..platform.height {
-        let mut rock_to_move = None;
-        for x in (0..platform.length).rev() {
-            match platform.get(&(x, y).into()) {
-                Some(Rock::Rounded) => {
-                    if rock_to_move.is_none() {
-                        rock_to_move = Some(x);
-                    }
-                }
-                Some(Rock::Cube) => rock_to_move = None,
-                None => {
-                    if let Some(old_rock) = rock_to_move.take() {
-                        platform.remove(&(old_rock, y).into());
-                        platform.insert((x, y).into(), Rock::Rounded);
+    /// Packs the rounded rocks between cube rocks towards the west or east
+    /// end of each stretch
+    fn slide_horizontal(&mut self, direction: Direction) {
+        for y in 0..self.height {
+            let row = &mut self.rounded[y * self.words..(y + 1) * self.words];
 
-                        rock_to_move = Some(old_rock - 1);
-                    }
-                }
+            for segment in &self.segments[y] {
+                let rocks = count_range(row, segment.clone());
+
+                set_range(row, segment.clone(), false);
+
+                let packed = if direction.towards_start() {
+                    segment.start..segment.start + rocks
+                } else {
+                    segment.end - rocks..segment.end
+                };
+
+                set_range(row, packed, true);
             }
         }
     }
 }
 
-pub fn slide_platform_south(platform: &mut Platform) {
-    for x in 0..platform.length {
-        let mut rock_to_move = None;
-        for y in 0..platform.height {
-            match platform.get(&(x, y).into()) {
-                Some(Rock::Rounded) => {
-                    if rock_to_move.is_none() {
-                        rock_to_move = Some(y);
-                    }
-                }
-                Some(Rock::Cube) => rock_to_move = None,
-                None => {
-                    if let Some(old_rock) = rock_to_move.take() {
-                        platform.remove(&(x, old_rock).into());
-                        platform.insert((x, y).into(), Rock::Rounded);
+/// Stretches of `0..length` between the cube rocks
+fn stretches(length: usize, is_cube: impl Fn(usize) -> bool) -> Vec<Range<usize>> {
+    let mut stretches = Vec::new();
+    let mut start = 0;
 
-                        rock_to_move = Some(old_rock + 1);
-                    }
-                }
+    for i in 0..=length {
+        if i == length || is_cube(i) {
+            if start < i {
+                stretches.push(start..i);
             }
+            start = i + 1;
         }
     }
+
+    stretches
 }
 
-pub fn slide_platform_east(platform: &mut Platform) {
-    for y in 0..platform.height {
-        let mut rock_to_move = None;
-        for x in 0..platform.length {
-            match platform.get(&(x, y).into()) {
-                Some(Rock::Rounded) => {
-                    if rock_to_move.is_none() {
-                        rock_to_move = Some(x);
-                    }
-                }
-                Some(Rock::Cube) => rock_to_move = None,
-                None => {
-                    if let Some(old_rock) = rock_to_move.take() {
-                        platform.remove(&(old_rock, y).into());
-                        platform.insert((x, y).into(), Rock::Rounded);
+/// Positions of the set bits of a word
+fn set_bits(mut word: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        let bit = word.trailing_zeros() as usize;
+        word &= word.wrapping_sub(1);
 
-                        rock_to_move = Some(old_rock + 1);
-                    }
+        (bit < WORD).then_some(bit)
+    })
+}
+
+fn is_set(row: &[u64], x: usize) -> bool {
+    row[x / WORD] >> (x % WORD) & 1 == 1
+}
+
+/// Masks of the words covered by a range of bits
+fn word_masks(range: Range<usize>) -> impl Iterator<Item = (usize, u64)> {
+    let words = range.start / WORD..range.end.div_ceil(WORD);
+
+    words.map(move |w| {
+        let start = range.start.max(w * WORD) - w * WORD;
+        let end = range.end.min((w + 1) * WORD) - w * WORD;
+
+        let mask = if end - start == WORD {
+            u64::MAX
+        } else {
+            ((1 << (end - start)) - 1) << start
+        };
+
+        (w, mask)
+    })
+}
+
+fn count_range(row: &[u64], range: Range<usize>) -> usize {
+    word_masks(range)
+        .map(|(w, mask)| (row[w] & mask).count_ones() as usize)
+        .sum()
+}
+
+fn set_range(row: &mut [u64], range: Range<usize>, value: bool) {
+    for (w, mask) in word_masks(range) {
+        if value {
+            row[w] |= mask;
+        } else {
+            row[w] &= !mask;
+        }
+    }
+}
+
+impl Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.length {
+                match self.get(x, y) {
+                    Some(rock) => f.write_str(&rock.to_string())?,
+                    None => f.write_char('.')?,
                 }
             }
+            f.write_char('\n')?;
         }
+        Ok(())
     }
 }
 
@@ -192,19 +406,243 @@ pub fn parse_platform(input: &str) -> Result<Platform> {
     let height = input.lines().count();
     let length = input.lines().next().context("Input not empty")?.len();
 
-    let matrix = input
+    ensure!(
+        input.lines().all(|l| l.chars().count() == length),
+        "All rows must have the same length"
+    );
+
+    let rocks = input
         .lines()
         .enumerate()
         .flat_map(|(y, l)| {
             l.chars().enumerate().filter_map(move |(x, c)| {
-                (c != '.').then_some(TryInto::<Rock>::try_into(c).map(|r| ((x, y).into(), r)))
+                (c != '.').then_some(TryInto::<Rock>::try_into(c).map(|r| ((x, y), r)))
             })
         })
-        .collect::<Result<_>>()?;
+        .collect::<Result<Vec<_>>>()?;
 
-    Ok(Platform {
-        matrix,
-        length,
-        height,
-    })
+    Platform::new(length, height, rocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    /// Tilts the rendered platform one rock step at a time until nothing moves
+    fn naive_slide(
+        platform: &Platform,
+        step: fn(usize, usize) -> Option<(usize, usize)>,
+    ) -> String {
+        let mut grid = platform
+            .to_string()
+            .lines()
+            .map(|l| l.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut moved = true;
+
+        while moved {
+            moved = false;
+
+            for y in 0..platform.height {
+                for x in 0..platform.length {
+                    let Some((nx, ny)) = step(x, y) else {
+                        continue;
+                    };
+
+                    if grid[y][x] == 'O' && grid.get(ny).and_then(|row| row.get(nx)) == Some(&'.') {
+                        grid[y][x] = '.';
+                        grid[ny][nx] = 'O';
+                        moved = true;
+                    }
+                }
+            }
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<String>() + "\n")
+            .collect()
+    }
+
+    /// The cell a rounded rock rolls into when tilting
+    fn naive_step(direction: Direction) -> fn(usize, usize) -> Option<(usize, usize)> {
+        match direction {
+            Direction::North => |x, y| Some((x, y.checked_sub(1)?)),
+            Direction::West => |x, y| Some((x.checked_sub(1)?, y)),
+            Direction::South => |x, y| Some((x, y + 1)),
+            Direction::East => |x, y| Some((x + 1, y)),
+        }
+    }
+
+    #[test]
+    fn test_wide_platform_matches_naive() -> Result<()> {
+        // NOTE: rows wider than a word, with rocks across word boundaries
+        let input = (0..12_usize)
+            .map(|y| {
+                (0..150_usize)
+                    .map(|x| match (x * 7 + y * 13 + x * y) % 11 {
+                        0 | 5 => '#',
+                        1 | 3 | 8 => 'O',
+                        _ => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let platform = parse_platform(&input)?;
+
+        for direction in SPIN_CYCLE {
+            let mut tilted = platform.clone();
+            tilted.tilt(direction);
+
+            assert_eq!(
+                naive_slide(&platform, naive_step(direction)),
+                tilted.to_string()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_equality_and_load() -> Result<()> {
+        let mut platform = parse_platform("O.#\n.O.\n#.O")?;
+        let other = parse_platform("O.#\n.O.\n#.O")?;
+
+        assert_eq!(platform, other);
+
+        platform.tilt(Direction::North);
+
+        assert_ne!(platform, other);
+        assert_eq!("OO#\n..O\n#..\n", platform.to_string());
+        assert_eq!(3 + 3 + 2, platform.load(Direction::North));
+        assert_eq!(3 + 2 + 1, platform.load(Direction::West));
+        assert_eq!(1 + 1 + 2, platform.load(Direction::South));
+        assert_eq!(1 + 2 + 3, platform.load(Direction::East));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_matches_rocks() -> Result<()> {
+        let platform = parse_platform(
+            &(0..5_usize)
+                .map(|y| {
+                    (0..130_usize)
+                        .map(|x| if (x + y) % 3 == 0 { 'O' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )?;
+
+        let load = |weight: fn(&Coordinates) -> u64| {
+            platform
+                .rocks()
+                .filter(|(_, rock)| *rock == Rock::Rounded)
+                .map(|(c, _)| weight(&c))
+                .sum::<u64>()
+        };
+
+        assert_eq!(load(|c| 5 - c.y), platform.load(Direction::North));
+        assert_eq!(load(|c| 130 - c.x), platform.load(Direction::West));
+        assert_eq!(load(|c| c.y + 1), platform.load(Direction::South));
+        assert_eq!(load(|c| c.x + 1), platform.load(Direction::East));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tilt_sequence() -> Result<()> {
+        let platform = parse_platform("O.#.\n..O.\n#..O\n.O..")?;
+
+        let mut spun = platform.clone();
+        spun.tilt_sequence(&parse_directions("NWSE")?);
+
+        let mut cycled = platform.clone();
+        cycled.tilt_sequence(&SPIN_CYCLE);
+
+        assert_eq!(cycled, spun);
+
+        let mut reversed = platform;
+        reversed.tilt_sequence(&parse_directions("EN")?);
+
+        assert_eq!(".O#O\n...O\n#..O\n....\n", reversed.to_string());
+        assert_eq!(
+            "NWSE",
+            SPIN_CYCLE
+                .iter()
+                .map(ToString::to_string)
+                .collect::<String>()
+        );
+        assert!(parse_directions("NWX").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_platform() {
+        assert!(parse_platform("O.\n...").is_err());
+        assert!(parse_platform("O.x").is_err());
+        assert!(parse_platform("").is_err());
+    }
+
+    /// Generates a platform up to three words wide, so rocks cross word
+    /// boundaries
+    fn platform() -> impl Strategy<Value = String> {
+        let cell = prop_oneof![3 => Just('.'), 2 => Just('O'), 1 => Just('#')];
+
+        grids::grid(cell, 1..150, 1..12).prop_map(|rows| grids::text(&rows))
+    }
+
+    fn direction() -> impl Strategy<Value = Direction> {
+        prop::sample::select(SPIN_CYCLE.to_vec())
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_generated_platform(input in platform()) {
+            let platform = parse_platform(&input).expect("Generated platform is valid");
+
+            prop_assert_eq!(input + "\n", platform.to_string());
+        }
+
+        #[test]
+        fn test_tilt_matches_naive(input in platform(), direction in direction()) {
+            let platform = parse_platform(&input).expect("Generated platform is valid");
+
+            let mut tilted = platform.clone();
+            tilted.tilt(direction);
+
+            prop_assert_eq!(naive_slide(&platform, naive_step(direction)), tilted.to_string());
+            prop_assert_eq!(platform.rocks().count(), tilted.rocks().count());
+
+            let mut again = tilted.clone();
+            again.tilt(direction);
+
+            prop_assert_eq!(tilted, again);
+        }
+
+        #[test]
+        fn test_load_matches_generated_rocks(input in platform(), direction in direction()) {
+            let platform = parse_platform(&input).expect("Generated platform is valid");
+            let (length, height) = (platform.length as u64, platform.height as u64);
+
+            let expected = platform
+                .rocks()
+                .filter(|(_, rock)| *rock == Rock::Rounded)
+                .map(|(c, _)| match direction {
+                    Direction::North => height - c.y,
+                    Direction::West => length - c.x,
+                    Direction::South => c.y + 1,
+                    Direction::East => c.x + 1,
+                })
+                .sum::<u64>();
+
+            prop_assert_eq!(expected, platform.load(direction));
+        }
+    }
 }
@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    day_15::parse_manual(input).for_each(|i| drop(day_15::parse_instruction(i)));
+});
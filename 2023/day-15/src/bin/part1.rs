@@ -1,9 +1,19 @@
-fn main() {
-    let input = include_str!("../../input.txt");
+use anyhow::Result;
 
-    let result = process(input);
+fn main() -> Result<()> {
+    timing::start();
+
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input);
+    timing::solved();
 
     println!("{result}");
+
+    Ok(())
 }
 
 #[inline]
@@ -70,6 +70,7 @@ fn remove_instruction(input: &mut &str) -> PResult<InitializationOperation> {
 mod tests {
     use super::*;
 
+    use proptest::prelude::*;
     use rstest::*;
 
     #[rstest]
@@ -86,6 +87,57 @@ mod tests {
     #[case("pc=6", 214)]
     #[case("ot=7", 231)]
     fn test_hash_algorithm(#[case] string: &str, #[case] hash: HASH) {
-        assert_eq!(hash, holiday_ascii_string_helper(string))
+        assert_eq!(hash, holiday_ascii_string_helper(string));
+    }
+
+    /// Generates an instruction as text with its label and focal length, if
+    /// it adds a lens
+    fn instruction() -> impl Strategy<Value = (String, Label, Option<u8>)> {
+        ("[a-z]{1,8}", prop::option::of(any::<u8>())).prop_map(|(label, focal_length)| {
+            let text = focal_length.map_or_else(
+                || format!("{label}-"),
+                |focal_length| format!("{label}={focal_length}"),
+            );
+
+            (text, label, focal_length)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_hash_matches_definition(string in "[ -~]{0,20}") {
+            let expected = string
+                .bytes()
+                .fold(0_u32, |current, c| (current + u32::from(c)) * 17 % 256);
+
+            prop_assert_eq!(expected, u32::from(holiday_ascii_string_helper(&string)));
+        }
+
+        #[test]
+        fn test_parse_generated_instruction((text, label, focal_length) in instruction()) {
+            let operation = parse_instruction(&text).expect("Generated instruction is valid");
+
+            match (operation, focal_length) {
+                (InitializationOperation::Add(lens), Some(focal_length)) => {
+                    prop_assert_eq!(label, lens.label);
+                    prop_assert_eq!(focal_length, lens.focal_length);
+                }
+                (InitializationOperation::Remove(removed), None) => prop_assert_eq!(label, removed),
+                _ => prop_assert!(false, "Wrong operation for {}", text),
+            }
+        }
+
+        #[test]
+        fn test_parse_generated_manual(
+            lines in prop::collection::vec(prop::collection::vec(instruction(), 1..10), 1..4)
+        ) {
+            let texts = lines
+                .iter()
+                .map(|line| line.iter().map(|(text, _, _)| text.as_str()).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let input = texts.iter().map(|line| line.join(",")).collect::<Vec<_>>().join("\n");
+
+            prop_assert_eq!(texts.concat(), parse_manual(&input).collect::<Vec<_>>());
+        }
     }
 }
@@ -4,9 +4,15 @@ use anyhow::Result;
 use day_16::Direction;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -16,6 +22,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let mut contraption = day_16::parse_contraption(input)?;
+    timing::parsed();
 
     let result = contraption
         .energize(Direction::Right, Some((0, 0)))
@@ -33,7 +40,7 @@ mod tests {
 
     #[test]
     fn test_example() -> Result<()> {
-        let input = r#".|...\....
+        let input = r".|...\....
 |.-.\.....
 .....|-...
 ........|.
@@ -42,7 +49,7 @@ mod tests {
 ..../.\\..
 .-.-/..|..
 .|....-|.\
-..//.|...."#;
+..//.|....";
 
         let result = process(input)?;
 
@@ -5,9 +5,15 @@ use day_16::{Contraption, Direction};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -17,6 +23,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let contraption = day_16::parse_contraption(input)?;
+    timing::parsed();
 
     let length = contraption.length;
     let height = contraption.height;
@@ -61,7 +68,7 @@ mod tests {
 
     #[test]
     fn test_example() -> Result<()> {
-        let input = r#".|...\....
+        let input = r".|...\....
 |.-.\.....
 .....|-...
 ........|.
@@ -70,7 +77,7 @@ mod tests {
 ..../.\\..
 .-.-/..|..
 .|....-|.\
-..//.|...."#;
+..//.|....";
 
         let result = process(input)?;
 
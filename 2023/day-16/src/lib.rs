@@ -5,7 +5,7 @@ use anyhow::{bail, Context, Result};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Splitter {
-    splitter_type: SplitterType,
+    kind: SplitterType,
     energized: bool,
 }
 
@@ -16,15 +16,15 @@ pub enum SplitterOutput {
 }
 
 impl Splitter {
-    const fn new(splitter_type: SplitterType) -> Self {
+    const fn new(kind: SplitterType) -> Self {
         Self {
-            splitter_type,
+            kind,
             energized: false,
         }
     }
 
     pub fn split(&mut self, direction: Direction) -> Option<SplitterOutput> {
-        match self.splitter_type {
+        match self.kind {
             SplitterType::Vertical => match direction {
                 Direction::Left | Direction::Right => self.energized.not().then(|| {
                     self.energized = true;
@@ -51,7 +51,7 @@ pub enum SplitterType {
 
 #[derive(Debug, Clone, Copy)]
 pub struct Mirror {
-    mirror_type: MirrorType,
+    kind: MirrorType,
     left_energized: bool,
     right_energized: bool,
 }
@@ -63,9 +63,9 @@ pub enum MirrorType {
 }
 
 impl Mirror {
-    const fn new(mirror_type: MirrorType) -> Self {
+    const fn new(kind: MirrorType) -> Self {
         Self {
-            mirror_type,
+            kind,
             left_energized: false,
             right_energized: false,
         }
@@ -73,7 +73,7 @@ impl Mirror {
 
     #[must_use]
     pub const fn get_redirected_direction(&self, direction: Direction) -> Direction {
-        match self.mirror_type {
+        match self.kind {
             MirrorType::Left => match direction {
                 Direction::Up => Direction::Right,
                 Direction::Down => Direction::Left,
@@ -92,7 +92,7 @@ impl Mirror {
     pub fn redirect(&mut self, direction: Direction) -> Option<Direction> {
         let new_direction = self.get_redirected_direction(direction);
 
-        match (self.mirror_type, direction) {
+        match (self.kind, direction) {
             (MirrorType::Left, Direction::Right | Direction::Down)
             | (MirrorType::Right, Direction::Right | Direction::Up) => {
                 self.left_energized.not().then(|| {
@@ -245,3 +245,95 @@ pub fn parse_contraption(input: &str) -> Result<Contraption> {
         height,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    /// Follows every beam over the rendered grid, remembering each cell and
+    /// direction already crossed
+    fn naive_energized(grid: &[Vec<char>], direction: Direction, position: Coordinates) -> usize {
+        let (length, height) = (grid[0].len(), grid.len());
+
+        let mut seen = HashSet::new();
+        let mut beams = vec![(position, direction)];
+
+        while let Some(((x, y), direction)) = beams.pop() {
+            if !seen.insert(((x, y), direction as u8)) {
+                continue;
+            }
+
+            let directions = match (grid[y][x], direction) {
+                ('/', Direction::Right) | ('\\', Direction::Left) => vec![Direction::Up],
+                ('/', Direction::Left) | ('\\', Direction::Right) => vec![Direction::Down],
+                ('/', Direction::Up) | ('\\', Direction::Down) => vec![Direction::Right],
+                ('/', Direction::Down) | ('\\', Direction::Up) => vec![Direction::Left],
+                ('|', Direction::Left | Direction::Right) => vec![Direction::Up, Direction::Down],
+                ('-', Direction::Up | Direction::Down) => vec![Direction::Left, Direction::Right],
+                _ => vec![direction],
+            };
+
+            for direction in directions {
+                let next = match direction {
+                    Direction::Up => y.checked_sub(1).map(|y| (x, y)),
+                    Direction::Down => (y + 1 < height).then_some((x, y + 1)),
+                    Direction::Left => x.checked_sub(1).map(|x| (x, y)),
+                    Direction::Right => (x + 1 < length).then_some((x + 1, y)),
+                };
+
+                beams.extend(next.map(|next| (next, direction)));
+            }
+        }
+
+        seen.into_iter()
+            .map(|(position, _)| position)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Generates a small contraption, mostly empty space
+    fn contraption() -> impl Strategy<Value = Vec<Vec<char>>> {
+        let cell = prop_oneof![
+            8 => Just('.'),
+            1 => Just('/'),
+            1 => Just('\\'),
+            1 => Just('|'),
+            1 => Just('-'),
+        ];
+
+        grids::grid(cell, 1..12, 1..12)
+    }
+
+    /// Generates a beam entering from an edge of a `length` by `height` grid
+    fn entry(length: usize, height: usize) -> impl Strategy<Value = (Direction, Coordinates)> {
+        prop_oneof![
+            (0..length).prop_map(|x| (Direction::Down, (x, 0))),
+            (0..height).prop_map(|y| (Direction::Right, (0, y))),
+            (0..length).prop_map(move |x| (Direction::Up, (x, height - 1))),
+            (0..height).prop_map(move |y| (Direction::Left, (length - 1, y))),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_energize_matches_naive(
+            (grid, (direction, position)) in contraption()
+                .prop_flat_map(|grid| {
+                    let entry = entry(grid[0].len(), grid.len());
+                    (Just(grid), entry)
+                })
+        ) {
+            let input = grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>();
+            let mut contraption =
+                parse_contraption(&input.join("\n")).expect("Generated contraption is valid");
+
+            let energized = contraption.energize(direction, Some(position)).collect::<HashSet<_>>();
+
+            prop_assert_eq!(naive_energized(&grid, direction, position), energized.len());
+        }
+    }
+}
@@ -4,9 +4,15 @@ use anyhow::Result;
 use day_17::{Actions, Crucible};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -16,6 +22,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u32> {
     let city = day_17::parse::city(input)?;
+    timing::parsed();
 
     Ok(day_17::get_heat_lost(city, Rc::new(UltraCrucible)))
 }
@@ -24,7 +31,7 @@ fn process(input: &str) -> Result<u32> {
 struct UltraCrucible;
 
 impl Crucible for UltraCrucible {
-    fn actions(&self, moved_straigth: usize) -> std::slice::Iter<Actions> {
+    fn actions(&self, moved_straigth: usize) -> std::slice::Iter<'_, Actions> {
         if moved_straigth < 4 {
             [Actions::Straight].iter()
         } else if moved_straigth < 10 {
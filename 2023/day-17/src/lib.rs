@@ -79,7 +79,7 @@ pub enum Actions {
 }
 
 pub trait Crucible: Debug {
-    fn actions(&self, moved_straigth: usize) -> std::slice::Iter<Actions>;
+    fn actions(&self, moved_straigth: usize) -> std::slice::Iter<'_, Actions>;
     fn can_stop(&self, moved_straight: usize) -> bool;
 }
 
@@ -263,3 +263,122 @@ pub fn get_heat_lost(city: City, crucible: Rc<dyn Crucible>) -> u32 {
 
     node.heat_lost()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cmp::Reverse;
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
+    const ACTIONS: [Actions; 3] = [Actions::Straight, Actions::Right, Actions::Left];
+
+    /// A crucible that must move `min` blocks before turning or stopping and
+    /// at most `max` blocks in a straight line
+    #[derive(Debug)]
+    struct TestCrucible {
+        min: usize,
+        max: usize,
+    }
+
+    impl Crucible for TestCrucible {
+        fn actions(&self, moved_straigth: usize) -> std::slice::Iter<'_, Actions> {
+            if moved_straigth < self.min {
+                ACTIONS[..1].iter()
+            } else if moved_straigth < self.max {
+                ACTIONS.iter()
+            } else {
+                ACTIONS[1..].iter()
+            }
+        }
+
+        fn can_stop(&self, moved_straight: usize) -> bool {
+            moved_straight >= self.min
+        }
+    }
+
+    /// Dijkstra over position, heading and blocks moved straight
+    fn naive_heat_lost(matrix: &[Vec<u8>], min: usize, max: usize) -> Option<u32> {
+        let (length, height) = (matrix[0].len(), matrix.len());
+        let goal = (length - 1, height - 1);
+
+        let mut best = HashMap::new();
+        let mut queue = std::collections::BinaryHeap::from([
+            Reverse((0, (0, 0), (1, 0), 0)),
+            Reverse((0, (0, 0), (0, 1), 0)),
+        ]);
+
+        while let Some(Reverse((heat, position, heading, straight))) = queue.pop() {
+            if position == goal && straight >= min {
+                return Some(heat);
+            }
+
+            if best.insert((position, heading, straight), heat).is_some() {
+                continue;
+            }
+
+            let (dx, dy): (isize, isize) = heading;
+            let turns = [(dy, -dx), (-dy, dx)];
+            let moves = (straight < max)
+                .then_some(((dx, dy), straight + 1))
+                .into_iter()
+                .chain(
+                    turns
+                        .into_iter()
+                        .filter(|_| straight >= min)
+                        .map(|turn| (turn, 1)),
+                );
+
+            for ((dx, dy), straight) in moves {
+                let x = position.0.checked_add_signed(dx).filter(|&x| x < length);
+                let y = position.1.checked_add_signed(dy).filter(|&y| y < height);
+
+                if let Some((x, y)) = x.zip(y) {
+                    let heat = heat + u32::from(matrix[y][x]);
+                    queue.push(Reverse((heat, (x, y), (dx, dy), straight)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Generates a small heat map, without zeros so the distance heuristic
+    /// stays admissible
+    fn city(min_side: usize) -> impl Strategy<Value = Vec<Vec<u8>>> {
+        grids::grid(1..=9_u8, min_side..min_side + 9, min_side..min_side + 9)
+    }
+
+    /// Generates the crucible limits with a city large enough to mostly reach
+    /// its goal
+    fn limits_and_city() -> impl Strategy<Value = ((usize, usize), Vec<Vec<u8>>)> {
+        (0..5_usize)
+            .prop_flat_map(|min| (Just(min), min.max(1)..min + 8))
+            .prop_flat_map(|(min, max)| (Just((min, max)), city(min + 1)))
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_city(matrix in city(1)) {
+            let city = parse::city(&grids::text(&matrix)).expect("Generated city is valid");
+
+            prop_assert_eq!((matrix[0].len(), matrix.len()), (city.length, city.height));
+            prop_assert_eq!(matrix, city.matrix);
+        }
+
+        #[test]
+        fn test_heat_lost_matches_naive(((min, max), matrix) in limits_and_city()) {
+            let expected = naive_heat_lost(&matrix, min, max);
+
+            // NOTE: the search panics when the crucible can not stop at the goal
+            prop_assume!(expected.is_some());
+
+            let city = City::try_from(matrix).expect("Generated city is valid");
+            let heat_lost = get_heat_lost(city, Rc::new(TestCrucible { min, max }));
+
+            prop_assert_eq!(expected, Some(heat_lost));
+        }
+    }
+}
@@ -3,9 +3,15 @@ use day_18::{DigInstruction, ShoeLacePick, RGB};
 use itertools::Itertools;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -61,7 +67,7 @@ U 2 (#7a21e3)";
 
         let result = process(input)?;
 
-        assert_eq!(952408144115, result);
+        assert_eq!(952_408_144_115, result);
 
         Ok(())
     }
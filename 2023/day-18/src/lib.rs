@@ -36,7 +36,11 @@ impl FromStr for RGB {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let (meters, direction) = s[1..].split_at(5);
+        let (meters, direction) = s
+            .strip_prefix('#')
+            .context("Color must start with #")?
+            .split_at_checked(5)
+            .context("Color must have a 5 digit hex value and a direction")?;
         let meters = u64::from(
             meters
                 .chars()
@@ -103,7 +107,7 @@ impl ShoeLacePick {
         self.last_vertex
     }
 
-    pub fn add_vertex(&mut self, next: Position, border_between_vertices: u64) {
+    pub const fn add_vertex(&mut self, next: Position, border_between_vertices: u64) {
         let last = self.last_vertex;
         self.value += last.x * next.y - last.y * next.x;
 
@@ -133,7 +137,11 @@ fn parse_dig_instruction(input: &str) -> Result<DigInstruction> {
         .map(|(direction, meters, color)| {
             let direction = direction.parse()?;
             let meters = meters.parse()?;
-            let color = color[1..color.len() - 1].parse()?;
+            let color = color
+                .strip_prefix('(')
+                .and_then(|c| c.strip_suffix(')'))
+                .context("Color must be surrounded by parenthesis")?
+                .parse()?;
 
             Ok(DigInstruction {
                 direction,
@@ -146,3 +154,110 @@ fn parse_dig_instruction(input: &str) -> Result<DigInstruction> {
 
     instruction
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    const DIRECTIONS: [(&str, char); 4] = [("U", '3'), ("D", '1'), ("L", '2'), ("R", '0')];
+
+    /// Generates the outline of a skyline, columns of random width and
+    /// height standing on a common floor, as `(direction, meters)` steps
+    fn skyline() -> impl Strategy<Value = Vec<(&'static str, u64)>> {
+        prop::collection::vec((1..5_u64, 1..8_u64), 1..6).prop_map(|columns| {
+            let mut steps = vec![("U", columns[0].1)];
+
+            for (i, &(width, height)) in columns.iter().enumerate() {
+                steps.push(("R", width));
+
+                match columns.get(i + 1).map_or(0, |next| next.1) {
+                    next if next > height => steps.push(("U", next - height)),
+                    next if next < height => steps.push(("D", height - next)),
+                    _ => {}
+                }
+            }
+
+            steps.push(("L", columns.iter().map(|column| column.0).sum()));
+            steps
+        })
+    }
+
+    fn direction_of(name: &str) -> Direction {
+        name.parse().expect("Generated direction is valid")
+    }
+
+    /// Counts the dug cells by tracing the trench and flooding its outside
+    fn naive_lagoon(steps: &[(&str, u64)]) -> u64 {
+        let mut position = Position::default();
+        let mut trench = HashSet::from([(0, 0)]);
+
+        for &(direction, meters) in steps {
+            for _ in 0..meters {
+                position = position.get_next(direction_of(direction), 1);
+                trench.insert((position.x, position.y));
+            }
+        }
+
+        let xs = trench.iter().map(|p| p.0).minmax().into_option();
+        let ys = trench.iter().map(|p| p.1).minmax().into_option();
+        let ((min_x, max_x), (min_y, max_y)) = xs.zip(ys).expect("Trench is not empty");
+        let (xs, ys) = (min_x - 1..=max_x + 1, min_y - 1..=max_y + 1);
+
+        let mut outside = HashSet::from([(min_x - 1, min_y - 1)]);
+        let mut queue = vec![(min_x - 1, min_y - 1)];
+
+        while let Some((x, y)) = queue.pop() {
+            for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if xs.contains(&next.0)
+                    && ys.contains(&next.1)
+                    && !trench.contains(&next)
+                    && outside.insert(next)
+                {
+                    queue.push(next);
+                }
+            }
+        }
+
+        (xs.count() * ys.count() - outside.len()) as u64
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_color(meters in 0..=0xF_FFFF_u64, direction in 0..4_usize) {
+            let (name, code) = DIRECTIONS[direction];
+            let color = format!("#{meters:05x}{code}");
+            let color = color.parse::<RGB>().expect("Generated color is valid");
+
+            prop_assert_eq!(meters, color.meters);
+            prop_assert_eq!(format!("{:?}", direction_of(name)), format!("{:?}", color.direction));
+        }
+
+        #[test]
+        fn test_lagoon_matches_naive(steps in skyline(), color in 0..=0xF_FFFF_u64) {
+            let input = steps
+                .iter()
+                .map(|(direction, meters)| format!("{direction} {meters} (#{color:05x}0)"))
+                .join("\n");
+
+            let lagoon = parse_dig_plan(&input)
+                .process_results(|it| {
+                    it.fold(ShoeLacePick::default(), |mut shoelace_pick, instruction| {
+                        let next = shoelace_pick
+                            .last_vertex()
+                            .get_next(instruction.direction, instruction.meters);
+
+                        shoelace_pick.add_vertex(next, instruction.meters);
+                        shoelace_pick
+                    })
+                })
+                .expect("Generated dig plan is valid")
+                .finish();
+
+            prop_assert_eq!(naive_lagoon(&steps), lagoon);
+        }
+    }
+}
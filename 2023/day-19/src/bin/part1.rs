@@ -2,9 +2,15 @@ use anyhow::Result;
 use day_19::RuleResult;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -14,6 +20,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let (workflows, parts) = day_19::parse_workflows_and_parts(input)?;
+    timing::parsed();
 
     let result = parts
         .iter()
@@ -4,9 +4,15 @@ use anyhow::Result;
 use day_19::{Rule, RuleResult, ValidParts, ValidPartsResult};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -16,6 +22,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let (workflows, _) = day_19::parse_workflows_and_parts(input)?;
+    timing::parsed();
 
     let valid_parts = ValidParts::default();
     let mut accepted_parts = Vec::new();
@@ -99,7 +106,7 @@ hdj{m>838:A,pv}
 
         let result = process(input)?;
 
-        assert_eq!(167409079868000, result);
+        assert_eq!(167_409_079_868_000, result);
 
         Ok(())
     }
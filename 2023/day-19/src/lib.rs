@@ -66,17 +66,21 @@ impl Range {
     }
 
     fn decrease(&mut self, value: u64, ordering: Ordering) -> bool {
-        if (self.left..=self.right).contains(&value) {
-            match ordering {
-                Ordering::Less => self.right = value - 1,
-                Ordering::LessEq => self.right = value,
-                Ordering::Greater => self.left = value + 1,
-                Ordering::GreaterEq => self.left = value,
-            }
-            true
-        } else {
-            false
+        let (left, right) = match ordering {
+            Ordering::Less if value == 0 => return false,
+            Ordering::Less => (self.left, self.right.min(value - 1)),
+            Ordering::LessEq => (self.left, self.right.min(value)),
+            Ordering::Greater => (self.left.max(value.saturating_add(1)), self.right),
+            Ordering::GreaterEq => (self.left.max(value), self.right),
+        };
+
+        if left > right {
+            return false;
         }
+
+        self.left = left;
+        self.right = right;
+        true
     }
 
     const fn len(&self) -> u64 {
@@ -141,9 +145,9 @@ pub enum Rule<'a> {
     },
 }
 
-impl<'a> Rule<'a> {
+impl Rule<'_> {
     #[must_use]
-    pub fn apply(&self, part: &Part) -> RuleResult {
+    pub fn apply(&self, part: &Part) -> RuleResult<'_> {
         match self {
             Rule::Condition {
                 rating,
@@ -166,7 +170,7 @@ impl<'a> Rule<'a> {
     }
 
     #[must_use]
-    pub fn apply_valid_parts(&self, valid_parts: &ValidParts) -> ValidPartsResult {
+    pub fn apply_valid_parts(&self, valid_parts: &ValidParts) -> ValidPartsResult<'_> {
         match self {
             Rule::Condition {
                 rating,
@@ -223,7 +227,7 @@ pub type Workflow<'a> = Vec<Rule<'a>>;
 /// # Errors
 /// If the workflows and parts are not separated by two '\n'
 /// or if any workflow or part is not valid
-pub fn parse_workflows_and_parts(input: &str) -> Result<(BTreeMap<&str, Workflow>, Vec<Part>)> {
+pub fn parse_workflows_and_parts(input: &str) -> Result<(BTreeMap<&str, Workflow<'_>>, Vec<Part>)> {
     let (workflows, parts) = input
         .split_once("\n\n")
         .context("Input must contain both parts")?;
@@ -238,9 +242,9 @@ pub fn parse_workflows_and_parts(input: &str) -> Result<(BTreeMap<&str, Workflow
     Ok((workflows, parts))
 }
 
-fn parse_workflow(input: &str) -> Result<(&str, Workflow)> {
+fn parse_workflow(input: &str) -> Result<(&str, Workflow<'_>)> {
     let (name, rules) = input
-        .get(..input.len() - 1)
+        .strip_suffix('}')
         .context("Must remove last bracket")?
         .split_once('{')
         .context("Rules must begin with a bracket")?;
@@ -265,7 +269,7 @@ fn parse_workflow(input: &str) -> Result<(&str, Workflow)> {
                     _ => bail!("Invalid ordering"),
                 };
 
-                let value = condition[2..].parse()?;
+                let value = chars.as_str().parse()?;
 
                 Ok(Rule::Condition {
                     rating,
@@ -284,7 +288,8 @@ fn parse_workflow(input: &str) -> Result<(&str, Workflow)> {
 
 fn parse_part(input: &str) -> Result<Part> {
     let [extremely_cool_looking, musical, aerodinamic, shiny] = input
-        .get(1..input.len() - 1)
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
         .context("Must remove brackets")?
         .split(',')
         .map(|n| {
@@ -303,3 +308,206 @@ fn parse_part(input: &str) -> Result<Part> {
         shiny,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    type Condition = (char, char, u64, usize);
+
+    fn workflow_name(i: usize) -> String {
+        if i == 0 {
+            "in".to_owned()
+        } else {
+            format!("w{i}")
+        }
+    }
+
+    /// Destinations only point to later workflows, so generated workflows never loop
+    fn destination(workflow: usize, workflows: usize, choice: usize) -> String {
+        match choice % (workflows - workflow + 1) {
+            0 => "A".to_owned(),
+            1 => "R".to_owned(),
+            n => workflow_name(workflow + n - 1),
+        }
+    }
+
+    fn workflows_input() -> impl Strategy<Value = String> {
+        let condition = (
+            prop::sample::select(&['x', 'm', 'a', 's'][..]),
+            prop::sample::select(&['<', '>'][..]),
+            1..=4000_u64,
+            any::<usize>(),
+        );
+        let workflow = (prop::collection::vec(condition, 0..4), any::<usize>());
+        let part = [1..=4000_u64, 1..=4000_u64, 1..=4000_u64, 1..=4000_u64];
+
+        (
+            prop::collection::vec(workflow, 1..6),
+            prop::collection::vec(part, 0..20),
+        )
+            .prop_map(|(workflows, parts)| {
+                let count = workflows.len();
+
+                let workflows = workflows
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (conditions, last)): (_, (Vec<Condition>, _))| {
+                        let rules = conditions
+                            .into_iter()
+                            .map(|(rating, ordering, value, choice)| {
+                                let destination = destination(i, count, choice);
+                                format!("{rating}{ordering}{value}:{destination}")
+                            })
+                            .chain(std::iter::once(destination(i, count, last)))
+                            .join(",");
+
+                        format!("{}{{{rules}}}", workflow_name(i))
+                    })
+                    .join("\n");
+
+                let parts = parts
+                    .into_iter()
+                    .map(|[x, m, a, s]| format!("{{x={x},m={m},a={a},s={s}}}"))
+                    .join("\n");
+
+                format!("{workflows}\n\n{parts}")
+            })
+    }
+
+    fn is_accepted(part: &Part, workflows: &BTreeMap<&str, Workflow>) -> bool {
+        let mut workflow = workflows["in"].iter();
+
+        loop {
+            let rule = workflow.next().expect("Workflows end with a direct rule");
+
+            match rule.apply(part) {
+                RuleResult::Continue => (),
+                RuleResult::Accept => break true,
+                RuleResult::Reject => break false,
+                RuleResult::SendTo(destination) => workflow = workflows[destination].iter(),
+            }
+        }
+    }
+
+    /// Splits the valid parts of a workflow into the accepted and rejected ones
+    fn split_valid_parts(
+        name: &str,
+        workflows: &BTreeMap<&str, Workflow>,
+        mut valid_parts: ValidParts,
+        accepted: &mut Vec<ValidParts>,
+        rejected: &mut Vec<ValidParts>,
+    ) {
+        let mut send = |result: RuleResult, parts: ValidParts| match result {
+            RuleResult::Accept => accepted.push(parts),
+            RuleResult::Reject => rejected.push(parts),
+            RuleResult::SendTo(name) => {
+                split_valid_parts(name, workflows, parts, accepted, rejected);
+            }
+            RuleResult::Continue => (),
+        };
+
+        for rule in &workflows[name] {
+            match rule.apply_valid_parts(&valid_parts) {
+                ValidPartsResult::Condition(parts_true, parts_false) => {
+                    if let Some((parts, result)) = parts_true {
+                        send(result, parts);
+                    }
+
+                    match parts_false {
+                        Some(parts) => valid_parts = parts,
+                        None => return,
+                    }
+                }
+                ValidPartsResult::Direct(result) => return send(result, valid_parts),
+            }
+        }
+    }
+
+    fn contains(valid_parts: &ValidParts, part: &Part) -> bool {
+        [
+            (
+                valid_parts.extremely_cool_looking,
+                part.extremely_cool_looking,
+            ),
+            (valid_parts.musical, part.musical),
+            (valid_parts.aerodinamic, part.aerodinamic),
+            (valid_parts.shiny, part.shiny),
+        ]
+        .into_iter()
+        .all(|(range, rating)| (range.left..=range.right).contains(&rating))
+    }
+
+    #[test]
+    fn test_decrease_range_already_inside_condition() {
+        // NOTE: `decrease` used to report no valid parts when the value was
+        // outside the range, dropping ranges that entirely meet the condition
+        let mut range = Range::new(1, 187);
+
+        assert!(range.decrease(188, Ordering::Less));
+        assert_eq!((1, 187), (range.left, range.right));
+        assert!(!range.decrease(188, Ordering::GreaterEq));
+
+        let mut range = Range::new(10, 20);
+
+        assert!(range.decrease(5, Ordering::Greater));
+        assert_eq!((10, 20), (range.left, range.right));
+        assert!(range.decrease(15, Ordering::LessEq));
+        assert_eq!((10, 15), (range.left, range.right));
+        assert!(!Range::new(0, 5).decrease(0, Ordering::Less));
+    }
+
+    #[test]
+    fn test_nested_condition_on_narrowed_range() -> Result<()> {
+        let input = "in{m<188:w1,A}\nw1{m<188:A,R}\n\n{x=1,m=1,a=1,s=1}";
+        let (workflows, _) = parse_workflows_and_parts(input)?;
+
+        let (mut accepted, mut rejected) = (Vec::new(), Vec::new());
+        split_valid_parts(
+            "in",
+            &workflows,
+            ValidParts::default(),
+            &mut accepted,
+            &mut rejected,
+        );
+
+        assert!(rejected.is_empty());
+        assert_eq!(
+            4000_u64.pow(4),
+            accepted.iter().map(ValidParts::total_rating).sum::<u64>()
+        );
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_valid_parts_partition_all_parts(input in workflows_input()) {
+            let (workflows, _) = parse_workflows_and_parts(&input).expect("Generated input is valid");
+
+            let (mut accepted, mut rejected) = (Vec::new(), Vec::new());
+            split_valid_parts("in", &workflows, ValidParts::default(), &mut accepted, &mut rejected);
+
+            let total = accepted.iter().chain(&rejected).map(ValidParts::total_rating).sum::<u64>();
+
+            prop_assert_eq!(4000_u64.pow(4), total);
+        }
+
+        #[test]
+        fn test_valid_parts_match_part_evaluation(input in workflows_input()) {
+            let (workflows, parts) = parse_workflows_and_parts(&input).expect("Generated input is valid");
+
+            let (mut accepted, mut rejected) = (Vec::new(), Vec::new());
+            split_valid_parts("in", &workflows, ValidParts::default(), &mut accepted, &mut rejected);
+
+            for part in &parts {
+                let in_accepted = accepted.iter().any(|valid_parts| contains(valid_parts, part));
+
+                prop_assert_eq!(is_accepted(part, &workflows), in_accepted);
+            }
+        }
+    }
+}
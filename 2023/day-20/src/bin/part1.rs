@@ -5,9 +5,15 @@ use anyhow::Result;
 use day_20::{Module, Order, Pulse};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -19,6 +25,7 @@ const TOTAL_TIMES: u32 = 1_000;
 #[inline]
 fn process(input: &str) -> Result<u32> {
     let mut modules = day_20::parse_module_configuration(input)?;
+    timing::parsed();
 
     let starting_modules = modules.clone();
 
@@ -88,7 +95,7 @@ mod tests {
 
         let result = process(input)?;
 
-        assert_eq!(32000000, result);
+        assert_eq!(32_000_000, result);
 
         Ok(())
     }
@@ -103,7 +110,7 @@ mod tests {
 
         let result = process(input)?;
 
-        assert_eq!(11687500, result);
+        assert_eq!(11_687_500, result);
 
         Ok(())
     }
@@ -6,9 +6,15 @@ use day_20::{Module, ModuleType};
 use itertools::Itertools;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -18,6 +24,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let modules = day_20::parse_module_configuration(input)?;
+    timing::parsed();
 
     let broadcaster = modules.get("broadcaster").context("goal must exist")?;
 
@@ -48,16 +55,14 @@ fn find_conjunction<'a>(
         .outputs
         .iter()
         .filter(|&name| {
-            matches!(
-                modules
-                    .get(name)
-                    .expect("Must exist or input is invalid")
-                    .state,
-                ModuleType::Conjunction(_)
-            )
+            modules
+                .get(name)
+                .is_some_and(|module| matches!(module.state, ModuleType::Conjunction(_)))
         })
         .exactly_one()
-        .map_err(|_| anyhow::format_err!("There must be exactly one conjunction that is the"))?;
+        .map_err(|_| {
+            anyhow::format_err!("Broadcaster output {name} must feed exactly one conjunction")
+        })?;
 
     Ok((first_module, conjunction_name))
 }
@@ -1,7 +1,7 @@
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
 pub enum Pulse {
@@ -46,7 +46,7 @@ pub struct Module<'a> {
     pub state: ModuleType<'a>,
 }
 
-impl<'a> Module<'a> {
+impl Module<'_> {
     pub fn recieve_and_send(
         &mut self,
         sender: &str,
@@ -72,7 +72,7 @@ pub enum ModuleType<'a> {
     Broadcast,
 }
 
-impl<'a> ModuleType<'a> {
+impl ModuleType<'_> {
     fn recieve_and_send(&mut self, sender: &str, pulse: Pulse) -> Option<Pulse> {
         match self {
             ModuleType::FlipFlop(state) => match pulse {
@@ -105,7 +105,7 @@ impl<'a> ModuleType<'a> {
 ///
 /// # Errors
 /// If a module's format is not valid
-pub fn parse_module_configuration(input: &str) -> Result<BTreeMap<&str, Module>> {
+pub fn parse_module_configuration(input: &str) -> Result<BTreeMap<&str, Module<'_>>> {
     let mut map = input
         .lines()
         .map(parse_module)
@@ -131,7 +131,7 @@ pub fn parse_module_configuration(input: &str) -> Result<BTreeMap<&str, Module>>
     Ok(map)
 }
 
-fn parse_module(input: &str) -> Result<(&str, Module)> {
+fn parse_module(input: &str) -> Result<(&str, Module<'_>)> {
     let (name, outputs) = input
         .split_once(" -> ")
         .context("Module name and outputs must be separated by an arrow")?;
@@ -142,7 +142,7 @@ fn parse_module(input: &str) -> Result<(&str, Module)> {
         Some('%') => (&name[1..], ModuleType::FlipFlop(State::default())),
         Some('&') => (&name[1..], ModuleType::Conjunction(HashMap::default())),
         Some(_) if name == "broadcaster" => (name, ModuleType::Broadcast),
-        _ => unreachable!("Invalid module type"),
+        _ => bail!("Invalid module type"),
     };
 
     let module = Module {
@@ -153,3 +153,101 @@ fn parse_module(input: &str) -> Result<(&str, Module)> {
 
     Ok((name, module))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    /// Generates a module configuration with a broadcaster and flip-flop or conjunction modules,
+    /// where outputs may also point to untyped modules like `rx`
+    fn configuration_input() -> impl Strategy<Value = String> {
+        let module = (
+            any::<bool>(),
+            prop::collection::vec(any::<prop::sample::Index>(), 1..4),
+        );
+
+        (
+            prop::collection::vec(any::<prop::sample::Index>(), 1..4),
+            prop::collection::vec(module, 1..8),
+        )
+            .prop_map(|(broadcaster, modules)| {
+                let names = (0..modules.len())
+                    .map(|i| format!("m{i}"))
+                    .chain(std::iter::once("rx".to_owned()))
+                    .collect_vec();
+
+                let outputs = |outputs: Vec<prop::sample::Index>| {
+                    outputs
+                        .into_iter()
+                        .map(|i| i.get(&names))
+                        .unique()
+                        .join(", ")
+                };
+
+                let modules = modules
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (is_flip_flop, o))| {
+                        let kind = if is_flip_flop { '%' } else { '&' };
+                        format!("{kind}m{i} -> {}", outputs(o))
+                    })
+                    .collect_vec();
+
+                std::iter::once(format!("broadcaster -> {}", outputs(broadcaster)))
+                    .chain(modules)
+                    .join("\n")
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_all_modules(input in configuration_input()) {
+            let modules = parse_module_configuration(&input).expect("Generated configuration is valid");
+
+            prop_assert_eq!(input.lines().count(), modules.len());
+            prop_assert!(modules.contains_key("broadcaster"));
+        }
+
+        #[test]
+        fn test_conjunction_inputs_are_its_senders(input in configuration_input()) {
+            let modules = parse_module_configuration(&input).expect("Generated configuration is valid");
+
+            for (&name, module) in &modules {
+                if let ModuleType::Conjunction(inputs) = &module.state {
+                    let senders = modules
+                        .values()
+                        .filter(|m| m.outputs.contains(&name))
+                        .map(|m| m.name)
+                        .collect::<BTreeSet<_>>();
+
+                    prop_assert_eq!(senders, inputs.keys().copied().collect());
+                    prop_assert!(inputs.values().all(|&p| p == Pulse::Low));
+                }
+            }
+        }
+
+        #[test]
+        fn test_flip_flop_toggles_on_low_pulses(pulses in prop::collection::vec(any::<bool>(), 0..50)) {
+            let mut flip_flop = ModuleType::FlipFlop(State::default());
+            let mut lows = 0;
+
+            for high in pulses {
+                let pulse = if high { Pulse::High } else { Pulse::Low };
+                let sent = flip_flop.recieve_and_send("broadcaster", pulse);
+
+                if high {
+                    prop_assert_eq!(None, sent);
+                } else {
+                    lows += 1;
+                    let expected = if lows % 2 == 1 { Pulse::High } else { Pulse::Low };
+                    prop_assert_eq!(Some(expected), sent);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,15 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input, 64)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input, 64)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,6 +19,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str, steps: u32) -> Result<u64> {
     let garden = day_21::parse_garden(input)?;
+    timing::parsed();
 
     let result = day_21::random_walk_posibilities(&garden, steps);
 
@@ -44,4 +51,15 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_non_square_garden() -> Result<()> {
+        let input = ".....
+..S#.";
+
+        assert_eq!(2, process(input, 1)?);
+        assert_eq!(4, process(input, 2)?);
+
+        Ok(())
+    }
 }
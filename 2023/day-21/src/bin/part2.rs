@@ -1,9 +1,15 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input, 26_501_365)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input, 26_501_365)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,6 +19,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str, steps: u32) -> Result<u64> {
     let mut garden = day_21::parse_garden(input)?;
+    timing::parsed();
 
     garden.make_infinite();
 
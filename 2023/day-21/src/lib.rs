@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use itertools::Itertools;
 
 #[derive(Debug, Clone, Copy)]
@@ -75,10 +75,10 @@ impl Garden {
             (usize::try_from(x).ok()?, usize::try_from(y).ok()?)
         };
 
-        (x < self.length && y < self.height).then(|| self.matrix[y * self.height + x])
+        (x < self.length && y < self.height).then(|| self.matrix[y * self.length + x])
     }
 
-    pub fn make_infinite(&mut self) {
+    pub const fn make_infinite(&mut self) {
         self.infinite = true;
     }
 }
@@ -103,9 +103,9 @@ impl<T: Default> Default for Switcher<T> {
 }
 
 impl<T> Switcher<T> {
-    fn init(&mut self) {
-        self.current = &mut self.v1;
-        self.other = &mut self.v2;
+    const fn init(&mut self) {
+        self.current = &raw mut self.v1;
+        self.other = &raw mut self.v2;
     }
 
     fn is_init(&mut self) -> bool {
@@ -129,6 +129,17 @@ impl<T> Switcher<T> {
 
 #[must_use]
 pub fn random_walk_posibilities(garden: &Garden, steps: u32) -> u64 {
+    // NOTE: every other plot can step back the way it was reached, a start
+    // walled in by rocks can not take a single step
+    let stuck = garden
+        .start()
+        .next()
+        .all(|p| !matches!(garden.get(p), Some(Feature::Plot)));
+
+    if stuck && steps > 0 {
+        return 0;
+    }
+
     let mut frontier = HashSet::from([garden.start()]);
     let mut last_frontier = HashSet::default();
     let mut switcher = Switcher::<u64>::default();
@@ -163,7 +174,17 @@ pub fn random_walk_posibilities(garden: &Garden, steps: u32) -> u64 {
 /// If there is an invalid garden feature
 pub fn parse_garden(input: &str) -> Result<Garden> {
     let height = input.lines().count();
-    let length = input.len() / height - 1;
+    let length = input
+        .lines()
+        .next()
+        .context("Garden must not be empty")?
+        .chars()
+        .count();
+
+    ensure!(
+        input.lines().all(|l| l.chars().count() == length),
+        "All garden rows must have the same length"
+    );
 
     let mut start = None;
 
@@ -193,3 +214,65 @@ pub fn parse_garden(input: &str) -> Result<Garden> {
 
     Ok(Garden::new(matrix, height, length, start))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("S", 0, 1)]
+    #[case("S", 1, 0)]
+    #[case("S", 2, 0)]
+    #[case(".#.\n#S#\n.#.", 4, 0)]
+    #[case("...\n.S.\n...", 2, 5)]
+    fn test_walled_in_start(
+        #[case] input: &str,
+        #[case] steps: u32,
+        #[case] expected: u64,
+    ) -> Result<()> {
+        let garden = parse_garden(input)?;
+
+        assert_eq!(expected, random_walk_posibilities(&garden, steps));
+
+        Ok(())
+    }
+
+    /// Generates a small garden, a third of it rocks, with a start plot
+    fn garden() -> impl Strategy<Value = String> {
+        let cell = prop_oneof![2 => Just('.'), 1 => Just('#')];
+
+        grids::with_marker(grids::grid(cell, 1..8, 1..8), 'S')
+            .prop_map(|garden| grids::text(&garden))
+    }
+
+    /// Keeps every plot reachable after each step
+    fn naive_walk(garden: &Garden, steps: u32) -> u64 {
+        let mut reached = HashSet::from([garden.start()]);
+
+        for _ in 0..steps {
+            reached = reached
+                .iter()
+                .flat_map(|&position| position.next())
+                .filter(|&position| matches!(garden.get(position), Some(Feature::Plot)))
+                .collect();
+        }
+
+        reached.len() as u64
+    }
+
+    proptest! {
+        #[test]
+        fn test_walk_matches_naive(input in garden(), steps in 0..30_u32, infinite: bool) {
+            let mut garden = parse_garden(&input).expect("Generated garden is valid");
+
+            if infinite {
+                garden.make_infinite();
+            }
+
+            prop_assert_eq!(naive_walk(&garden, steps), random_walk_posibilities(&garden, steps));
+        }
+    }
+}
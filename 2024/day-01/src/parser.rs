@@ -23,3 +23,33 @@ pub fn parse(input: &str) -> Result<(HistoricLocations, HistoricLocations)> {
             },
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    fn locations() -> impl Strategy<Value = Vec<(u64, u64)>> {
+        prop::collection::vec((0..100_000_u64, 0..100_000_u64), 0..50)
+    }
+
+    /// Sorts the ids so lists compare regardless of order
+    fn sorted(ids: impl Iterator<Item = u64>) -> Vec<u64> {
+        ids.sorted().collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_sorts_both_lists(pairs in locations()) {
+            let input = pairs.iter().map(|(id1, id2)| format!("{id1}   {id2}")).join("\n");
+
+            let (hl1, hl2) = parse(&input).expect("Generated locations are valid");
+
+            let drain = |hl: HistoricLocations| sorted(hl.into_iter().map(|n| n.0));
+
+            prop_assert_eq!(sorted(pairs.iter().map(|p| p.0)), drain(hl1));
+            prop_assert_eq!(sorted(pairs.iter().map(|p| p.1)), drain(hl2));
+        }
+    }
+}
@@ -2,57 +2,81 @@ use std::cmp::Ordering;
 
 pub mod parser;
 
+/// Whether the levels are strictly monotonic with steps of at most three once
+/// up to `dampening` of them are removed
 #[must_use]
 pub fn is_safe(v: &[u64], dampening: usize) -> bool {
-    is_safe_rec(0, 1, v, dampening, Ordering::Equal)
+    [Ordering::Less, Ordering::Greater]
+        .into_iter()
+        .any(|ordering| {
+            // NOTE: longest safe run of kept levels ending on each level, every
+            // level left out of the longest one must be removed
+            let mut kept = vec![1; v.len()];
+
+            for next in 1..v.len() {
+                kept[next] = (0..next)
+                    .filter(|&current| is_safe_step(v[current], v[next], ordering))
+                    .map(|current| kept[current] + 1)
+                    .max()
+                    .unwrap_or(1);
+            }
+
+            v.len() - kept.iter().max().copied().unwrap_or_default() <= dampening
+        })
 }
 
-fn is_safe_rec(
-    current_idx: usize,
-    next_idx: usize,
-    v: &[u64],
-    dampening: usize,
-    old_ord: Ordering,
-) -> bool {
-    let (Some(&current), Some(&next)) = (v.get(current_idx), v.get(next_idx)) else {
-        return true;
-    };
-
-    let new_ord = current.cmp(&next);
-    let diff = current.abs_diff(next);
-
-    if diff == 0 || diff > 3 {
-        dampener(current_idx, next_idx, v, dampening, old_ord)
-    } else if matches!(old_ord, Ordering::Equal) || new_ord == old_ord {
-        is_safe_rec(next_idx, next_idx + 1, v, dampening, new_ord)
-    } else {
-        dampener(current_idx, next_idx, v, dampening, old_ord)
-    }
+fn is_safe_step(current: u64, next: u64, ordering: Ordering) -> bool {
+    current.cmp(&next) == ordering && (1..=3).contains(&current.abs_diff(next))
 }
 
-fn dampener(
-    current_idx: usize,
-    next_idx: usize,
-    v: &[u64],
-    dampening: usize,
-    ordering: Ordering,
-) -> bool {
-    if dampening > 0 {
-        if current_idx == 0 {
-            // Ignore first -> Ignore second
-            is_safe_rec(1, 2, v, dampening - 1, Ordering::Equal)
-                || is_safe_rec(0, 2, v, dampening - 1, Ordering::Equal)
-        } else if current_idx == 1 {
-            // Ignore prev -> Ignore current -> Ignore next
-            is_safe_rec(1, 2, v, dampening - 1, Ordering::Equal)
-                || is_safe_rec(0, 2, v, dampening - 1, Ordering::Equal)
-                || is_safe_rec(1, 3, v, dampening - 1, ordering)
-        } else {
-            // Ignore current -> Ignore next
-            is_safe_rec(current_idx - 1, next_idx, v, dampening - 1, ordering)
-                || is_safe_rec(current_idx, next_idx + 1, v, dampening - 1, ordering)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_is_safe_with_several_removals() {
+        // NOTE: the first two levels must go, after which the report falls
+        assert!(!is_safe(&[10, 10, 11, 10, 9], 1));
+        assert!(is_safe(&[10, 10, 11, 10, 9], 2));
+
+        assert!(is_safe(&[1, 9, 9, 2, 3], 2));
+        assert!(!is_safe(&[1, 9, 9, 9, 2], 2));
+        assert!(is_safe(&[], 0));
+        assert!(is_safe(&[5], 0));
+    }
+
+    /// Generates a report whose neighbouring levels are mostly close enough
+    /// to be safe
+    fn report() -> impl Strategy<Value = Vec<u64>> {
+        (10..20_u64, prop::collection::vec(-4..=4_i64, 0..8)).prop_map(|(first, steps)| {
+            steps.iter().fold(vec![first], |mut levels, &step| {
+                let last = levels[levels.len() - 1];
+                levels.push(last.saturating_add_signed(step));
+                levels
+            })
+        })
+    }
+
+    /// Whether the levels, or any of them with up to `dampening` levels
+    /// removed, are strictly monotonic with steps of at most three
+    fn naive_is_safe(v: &[u64], dampening: usize) -> bool {
+        let steps = v.windows(2).map(|w| (w[0].cmp(&w[1]), w[0].abs_diff(w[1])));
+        let monotonic = v.windows(2).all(|w| w[0] < w[1]) || v.windows(2).all(|w| w[0] > w[1]);
+
+        (monotonic && steps.clone().all(|(_, diff)| (1..=3).contains(&diff)))
+            || (dampening > 0
+                && (0..v.len()).any(|i| {
+                    let removed = [&v[..i], &v[i + 1..]].concat();
+                    naive_is_safe(&removed, dampening - 1)
+                }))
+    }
+
+    proptest! {
+        #[test]
+        fn test_is_safe_matches_naive(v in report(), dampening in 0..4_usize) {
+            prop_assert_eq!(naive_is_safe(&v, dampening), is_safe(&v, dampening), "{:?}", v);
         }
-    } else {
-        false
     }
 }
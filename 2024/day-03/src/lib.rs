@@ -26,7 +26,7 @@ impl Default for State {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
     Mul(u64, u64),
     Do,
@@ -46,7 +46,7 @@ impl Program {
 }
 
 impl Instruction {
-    pub fn run(self, state: &mut State) -> Option<u64> {
+    pub const fn run(self, state: &mut State) -> Option<u64> {
         match self {
             Self::Mul(n1, n2) => {
                 if state.enabled {
@@ -15,3 +15,75 @@ pub fn parse(input: &str) -> Result<Vec<Instruction>> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Program;
+
+    use proptest::prelude::*;
+
+    /// Corrupted memory that never completes an instruction, whatever is
+    /// written around it
+    const NOISE: [&str; 15] = [
+        "x",
+        "&",
+        "!^",
+        "_",
+        "+",
+        "]",
+        "?",
+        "(",
+        "mul",
+        "do",
+        "don't",
+        "mul[3,7]",
+        "mul(32,64]",
+        "mul ( 2 , 4 )",
+        "mul(4*",
+    ];
+
+    /// Generates instructions, `None` for corrupted memory, as text
+    fn memory() -> impl Strategy<Value = Vec<(Option<Instruction>, String)>> {
+        let instruction = prop_oneof![
+            (0..1000_u64, 0..1000_u64)
+                .prop_map(|(n1, n2)| (Some(Instruction::Mul(n1, n2)), format!("mul({n1},{n2})"))),
+            Just((Some(Instruction::Do), "do()".to_string())),
+            Just((Some(Instruction::Dont), "don't()".to_string())),
+            prop::sample::select(NOISE.to_vec()).prop_map(|noise| (None, noise.to_string())),
+        ];
+
+        prop::collection::vec(instruction, 0..30)
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_memory(memory in memory(), conditionals: bool) {
+            let input = memory.iter().map(|(_, text)| text.as_str()).collect::<String>();
+
+            let generated = memory.iter().filter_map(|(instruction, _)| *instruction);
+            let generated = generated.collect::<Vec<_>>();
+
+            let (_, expected) = generated.iter().fold((true, 0), |(enabled, sum), instruction| {
+                match instruction {
+                    Instruction::Mul(n1, n2) if enabled || !conditionals => {
+                        (enabled, sum + n1 * n2)
+                    }
+                    Instruction::Mul(..) => (enabled, sum),
+                    Instruction::Do => (true, sum),
+                    Instruction::Dont => (false, sum),
+                }
+            });
+
+            let instructions = parse(&input).expect("Generated memory is valid");
+
+            prop_assert_eq!(&generated, &instructions);
+
+            let mut program = Program::from(instructions);
+            program.conditionals = conditionals;
+
+            prop_assert_eq!(expected, program.run());
+        }
+    }
+}
@@ -2,9 +2,15 @@ use anyhow::Result;
 use day_04::{Letter, I, U};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -27,6 +33,7 @@ const XMAS: [Letter; 4] = [Letter::X, Letter::M, Letter::A, Letter::S];
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let word_search = day_04::parser::parse(input)?;
+    timing::parsed();
 
     let U(x, y) = word_search.size();
 
@@ -2,9 +2,15 @@ use anyhow::Result;
 use day_04::{Letter, I, U};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -23,6 +29,7 @@ const AMSMS: [Letter; 5] = [Letter::A, Letter::M, Letter::S, Letter::M, Letter::
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let word_search = day_04::parser::parse(input)?;
+    timing::parsed();
 
     let U(x, y) = word_search.size();
 
@@ -91,3 +91,95 @@ impl WordSearch {
             .sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    const XMAS: [Letter; 4] = [Letter::X, Letter::M, Letter::A, Letter::S];
+
+    /// The next three letters in each of the eight directions
+    fn xmas_options() -> Vec<[I; 3]> {
+        [
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+        ]
+        .into_iter()
+        .map(|(x, y)| [I(x, y), I(2 * x, 2 * y), I(3 * x, 3 * y)])
+        .collect()
+    }
+
+    fn word_search() -> impl Strategy<Value = Vec<String>> {
+        let letter = prop::sample::select(&['X', 'M', 'A', 'S'][..]);
+
+        grids::grid(letter, 1..12, 1..12)
+            .prop_map(|rows| rows.into_iter().map(String::from_iter).collect())
+    }
+
+    /// Counts `XMAS` forwards and backwards along every row, column and
+    /// diagonal of the text
+    fn naive_xmas(rows: &[String]) -> u64 {
+        let grid = rows
+            .iter()
+            .map(|row| row.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let (height, length) = (grid.len(), grid[0].len());
+
+        let cells = |predicate: &dyn Fn(usize, usize) -> bool| {
+            (0..height)
+                .flat_map(|y| (0..length).map(move |x| (y, x)))
+                .filter(|&(y, x)| predicate(y, x))
+                .map(|(y, x)| grid[y][x])
+                .collect::<String>()
+        };
+
+        let lines = (0..height)
+            .map(|row| cells(&|y, _| y == row))
+            .chain((0..length).map(|column| cells(&|_, x| x == column)))
+            .chain((0..height + length).map(|d| cells(&|y, x| y + x == d)))
+            .chain((0..height + length).map(|d| cells(&|y, x| y + length == x + d)))
+            .collect::<Vec<_>>();
+
+        lines
+            .iter()
+            .map(|line| (line.matches("XMAS").count() + line.matches("SAMX").count()) as u64)
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_word_search(rows in word_search()) {
+            let word_search = parser::parse(&rows.join("\n")).expect("Generated search is valid");
+
+            prop_assert_eq!(U(rows.len(), rows[0].len()), word_search.size());
+
+            for (i, row) in rows.iter().enumerate() {
+                for (j, letter) in row.chars().enumerate() {
+                    prop_assert_eq!(Letter::try_from(letter).ok(), word_search.get(U(i, j)));
+                }
+            }
+        }
+
+        #[test]
+        fn test_search_matches_naive(rows in word_search()) {
+            let word_search = parser::parse(&rows.join("\n")).expect("Generated search is valid");
+            let U(x, y) = word_search.size();
+            let options = xmas_options();
+
+            let found = (0..x)
+                .flat_map(|i| (0..y).map(move |j| U(i, j)))
+                .map(|index| word_search.search(index, &XMAS, &options))
+                .sum::<u64>();
+
+            prop_assert_eq!(naive_xmas(&rows), found);
+        }
+    }
+}
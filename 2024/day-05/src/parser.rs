@@ -41,3 +41,40 @@ fn parse_updates(input: &str) -> Result<Vec<Vec<u64>>> {
         .map(|l| l.trim().split(',').map(|n| Ok(n.parse()?)).collect())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    fn rules() -> impl Strategy<Value = Vec<(u64, u64)>> {
+        prop::collection::vec((10..100_u64, 10..100_u64), 1..30)
+    }
+
+    fn updates() -> impl Strategy<Value = Vec<Vec<u64>>> {
+        prop::collection::vec(prop::collection::vec(10..100_u64, 1..10), 1..10)
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_manual(rules in rules(), updates in updates()) {
+            let input = format!(
+                "{}\n\n{}",
+                rules.iter().map(|(before, after)| format!("{before}|{after}")).join("\n"),
+                updates.iter().map(|update| update.iter().join(",")).join("\n"),
+            );
+
+            let (parsed_rules, parsed_updates) = parse(&input).expect("Generated manual is valid");
+
+            let mut expected = Rules::new();
+
+            for &(before, after) in &rules {
+                expected.entry(before).or_default().insert(after);
+            }
+
+            prop_assert_eq!(expected, parsed_rules);
+            prop_assert_eq!(updates, parsed_updates);
+        }
+    }
+}
@@ -1,9 +1,15 @@
 use anyhow::{Context, Result};
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -13,6 +19,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let (guard, x_lookup, y_lookup) = day_06::parser::parse(input)?;
+    timing::parsed();
 
     let path = day_06::get_out(&x_lookup, &y_lookup, guard).context("Guard enters a loop")?;
 
@@ -29,11 +29,7 @@ pub fn get_out(x_lookup: &Lookup, y_lookup: &Lookup, mut guard: Guard) -> Option
         };
 
         let Some(obstacle) = obstacle else {
-            let positions = match guard.direction() {
-                Direction::North | Direction::West => guard.advance(0),
-                Direction::South => guard.advance(x_lookup.len()),
-                Direction::East => guard.advance(y_lookup.len()),
-            };
+            let positions = guard.leave(y_lookup.len(), x_lookup.len());
 
             let added = add_visited(&mut visited, positions, direction);
             if !added {
@@ -64,3 +60,77 @@ pub fn add_visited(
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_get_out_visits_start_and_edge() -> anyhow::Result<()> {
+        for (input, expected) in [
+            ("^", HashSet::from([(0, 0)])),
+            (".\n^", HashSet::from([(0, 0), (0, 1)])),
+            ("#.\n^.", HashSet::from([(0, 1), (1, 1)])),
+        ] {
+            let (guard, x_lookup, y_lookup) = parser::parse(input)?;
+
+            let path = get_out(&x_lookup, &y_lookup, guard).map(collapse_directions);
+
+            assert_eq!(Some(expected), path, "{input:?}");
+        }
+
+        Ok(())
+    }
+
+    /// Generates a small lab, a fifth of it obstacles, with the guard
+    fn lab() -> impl Strategy<Value = Vec<Vec<char>>> {
+        let cell = prop_oneof![4 => Just('.'), 1 => Just('#')];
+
+        grids::with_marker(grids::grid(cell, 1..10, 1..10), '^')
+    }
+
+    /// Walks the guard one cell at a time, `None` once it repeats a cell
+    /// and direction
+    fn naive_walk(lab: &[Vec<char>]) -> Option<HashSet<(usize, usize)>> {
+        let (width, height) = (lab[0].len(), lab.len());
+        let start = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .find(|&(x, y)| lab[y][x] == '^')?;
+
+        let (mut position, mut heading) = (start, (0, -1));
+        let mut seen = HashSet::new();
+
+        while seen.insert((position, heading)) {
+            let x = position
+                .0
+                .checked_add_signed(heading.0)
+                .filter(|&x| x < width);
+            let y = position
+                .1
+                .checked_add_signed(heading.1)
+                .filter(|&y| y < height);
+
+            match x.zip(y) {
+                None => return Some(seen.into_iter().map(|(position, _)| position).collect()),
+                Some((x, y)) if lab[y][x] == '#' => heading = (-heading.1, heading.0),
+                Some(next) => position = next,
+            }
+        }
+
+        None
+    }
+
+    proptest! {
+        #[test]
+        fn test_get_out_matches_naive(lab in lab()) {
+            let (guard, x_lookup, y_lookup) =
+                parser::parse(&grids::text(&lab)).expect("Generated lab is valid");
+
+            let path = get_out(&x_lookup, &y_lookup, guard).map(collapse_directions);
+
+            prop_assert_eq!(naive_walk(&lab), path);
+        }
+    }
+}
@@ -45,12 +45,12 @@ impl Lookup {
     }
 
     #[must_use]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.0.len()
     }
 
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 }
@@ -86,7 +86,7 @@ impl Guard {
         let range = match old_direction {
             Direction::North => {
                 let p = std::mem::replace(&mut self.position.1, obstacle + 1);
-                obstacle + 1..p
+                obstacle + 1..p + 1
             }
             Direction::South => {
                 let p = std::mem::replace(&mut self.position.1, obstacle - 1);
@@ -99,7 +99,7 @@ impl Guard {
             }
             Direction::West => {
                 let p = std::mem::replace(&mut self.position.0, obstacle + 1);
-                obstacle + 1..p
+                obstacle + 1..p + 1
             }
         };
 
@@ -109,6 +109,25 @@ impl Guard {
         })
     }
 
+    /// Cells from the guard to the edge of a `width` by `height` lab, walking
+    /// straight out of it
+    pub fn leave(&self, width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+        let direction = self.direction;
+        let (x, y) = self.position;
+
+        let range = match direction {
+            Direction::North => 0..y + 1,
+            Direction::South => y..height,
+            Direction::East => x..width,
+            Direction::West => 0..x + 1,
+        };
+
+        range.map(move |n| match direction {
+            Direction::North | Direction::South => (x, n),
+            Direction::East | Direction::West => (n, y),
+        })
+    }
+
     #[must_use]
     pub const fn position(&self) -> (usize, usize) {
         self.position
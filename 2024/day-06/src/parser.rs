@@ -15,6 +15,10 @@ pub fn parse(input: &str) -> Result<(Guard, Lookup, Lookup)> {
     let mut start = None;
 
     for (y, l) in input.lines().enumerate() {
+        if l.trim().len() != width {
+            bail!("Invalid size")
+        }
+
         for (x, c) in l.trim().char_indices() {
             match c {
                 '^' => start = Some((x, y)),
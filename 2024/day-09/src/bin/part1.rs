@@ -3,9 +3,15 @@ use day_09::BlockKind;
 use itertools::Itertools;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
+    timing::solved();
 
     println!("{result}");
 
@@ -15,6 +21,7 @@ fn main() -> Result<()> {
 #[inline]
 fn process(input: &str) -> Result<u64> {
     let fs = day_09::parser::parse(input)?;
+    timing::parsed();
 
     let mut fragments = fs.into_iter().flat_map(|ds| ds.fragments()).collect_vec();
 
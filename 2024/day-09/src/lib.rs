@@ -37,7 +37,7 @@ impl DiskItem {
     }
 
     pub fn fragments(&self) -> impl Iterator<Item = BlockKind> {
-        std::iter::repeat(self.kind).take(self.len)
+        std::iter::repeat_n(self.kind, self.len)
     }
 
     #[must_use]
@@ -24,3 +24,57 @@ pub fn parse(input: &str) -> Result<Vec<DiskItem>> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    fn disk_map() -> impl Strategy<Value = String> {
+        prop::collection::vec(0..10_u32, 1..40).prop_map(|digits| {
+            digits
+                .into_iter()
+                .filter_map(|d| char::from_digit(d, 10))
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_alternates_files_and_space(input in disk_map()) {
+            let disk = parse(&input).expect("Generated disk map is valid");
+
+            prop_assert_eq!(input.len(), disk.len());
+
+            for (i, item) in disk.iter().enumerate() {
+                match item.kind {
+                    BlockKind::File(id) => prop_assert_eq!(i as u64 / 2, id),
+                    BlockKind::Empty => prop_assert_eq!(1, i % 2),
+                }
+            }
+        }
+
+        #[test]
+        fn test_fragments_match_digits(input in disk_map()) {
+            let disk = parse(&input).expect("Generated disk map is valid");
+
+            let expected = input.chars().filter_map(|c| c.to_digit(10)).sum::<u32>() as usize;
+
+            prop_assert_eq!(expected, disk.iter().flat_map(DiskItem::fragments).count());
+        }
+
+        #[test]
+        fn test_partition_keeps_length(len in 0..10_usize, taken in 0..10_usize) {
+            let (empty, rest) = DiskItem::empty(len).partition(taken);
+
+            match rest {
+                Some(rest) => {
+                    prop_assert_eq!(taken, empty.len);
+                    prop_assert_eq!(len, empty.len + rest.len);
+                }
+                None => prop_assert!(len <= taken),
+            }
+        }
+    }
+}
@@ -47,3 +47,103 @@ fn trail_dfs_inner(
 
     trail_tails.map_or(trails, |tt| tt.len() as u64)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    /// Generates a small map that climbs one step at a time along most
+    /// diagonals, so there are plenty of trails
+    fn topographic_map() -> impl Strategy<Value = Vec<Vec<u64>>> {
+        let offset = prop::sample::select(vec![0, 0, 0, 1, 5]);
+
+        grids::grid(offset, 1..9, 1..9).prop_map(|offsets| {
+            offsets
+                .iter()
+                .enumerate()
+                .map(|(y, row)| {
+                    let row = row.iter().enumerate();
+                    row.map(|(x, offset)| (x + y + offset) as u64 % 10)
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
+    /// Cells of a map with their heights, row by row
+    fn cells(map: &[Vec<u64>]) -> Vec<(PairIndex, u64)> {
+        let cells = map.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, &n)| (PairIndex::from((x, y)), n))
+        });
+
+        cells.collect()
+    }
+
+    /// Counts the trails up from every cell and the summits each cell
+    /// reaches, climbing down from the summits one height at a time
+    fn naive_trails(map: &[Vec<u64>]) -> Vec<(PairIndex, u64, u64)> {
+        let cells = cells(map);
+        let matrix = Matrix::from(map.to_vec());
+
+        let mut trails = std::collections::HashMap::new();
+        let mut summits = std::collections::HashMap::<PairIndex, HashSet<PairIndex>>::new();
+
+        for height in (0..=9).rev() {
+            for &(idx, _) in cells.iter().filter(|&&(_, n)| n == height) {
+                let up = idx
+                    .neighbors()
+                    .into_iter()
+                    .flatten()
+                    .filter(|&next| matrix.get(next) == Some(&(height + 1)))
+                    .collect::<Vec<_>>();
+
+                let (count, reached) = if height == 9 {
+                    (1, HashSet::from([idx]))
+                } else {
+                    let count = up.iter().map(|next| trails[next]).sum();
+                    let reached = up.iter().flat_map(|next| summits[next].clone()).collect();
+
+                    (count, reached)
+                };
+
+                trails.insert(idx, count);
+                summits.insert(idx, reached);
+            }
+        }
+
+        cells
+            .iter()
+            .map(|(idx, _)| (*idx, trails[idx], summits[idx].len() as u64))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_generated_map(map in topographic_map()) {
+            let input = map
+                .iter()
+                .map(|row| row.iter().map(ToString::to_string).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let matrix = parser::parse(&input).expect("Generated map is valid");
+
+            let parsed = matrix.iter().map(|(idx, &n)| (idx, n)).collect::<Vec<_>>();
+
+            prop_assert_eq!(cells(&map), parsed);
+        }
+
+        #[test]
+        fn test_trails_match_naive(map in topographic_map()) {
+            let matrix = Matrix::from(map.clone());
+
+            for (idx, trails, tails) in naive_trails(&map) {
+                prop_assert_eq!(trails, trails_dfs(&matrix, idx));
+                prop_assert_eq!(tails, trail_tails_dfs(&matrix, idx));
+            }
+        }
+    }
+}
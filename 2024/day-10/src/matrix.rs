@@ -54,7 +54,7 @@ impl<T> Matrix<T> {
     }
 
     #[must_use]
-    pub fn iter(&self) -> MatrixIter<T> {
+    pub fn iter(&self) -> MatrixIter<'_, T> {
         <&Self as IntoIterator>::into_iter(self)
     }
 }
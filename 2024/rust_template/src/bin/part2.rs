@@ -1,9 +1,14 @@
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    let input = include_str!("../../input.txt");
+    timing::start();
 
-    let result = process(input)?;
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../../input.txt").to_string(),
+    };
+
+    let result = process(&input)?;
 
     println!("{result}");
 
@@ -12,6 +17,7 @@ fn main() -> Result<()> {
 
 #[inline]
 fn process(_input: &str) -> Result<u64> {
+    // NOTE: call timing::parsed() right after parsing the input
     todo!()
 }
 
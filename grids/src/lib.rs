@@ -0,0 +1,77 @@
+//! Strategies generating small rectangular grids, shared by the property
+//! tests of the days whose input is a grid of characters.
+
+use std::{
+    fmt::{Debug, Display},
+    ops::Range,
+};
+
+use proptest::prelude::*;
+
+/// Generates a grid of `cell`s, its width and height drawn from the ranges.
+pub fn grid<S>(
+    cell: S,
+    width: Range<usize>,
+    height: Range<usize>,
+) -> impl Strategy<Value = Vec<Vec<S::Value>>>
+where
+    S: Strategy + Clone,
+{
+    (width, height).prop_flat_map(move |(width, height)| {
+        prop::collection::vec(prop::collection::vec(cell.clone(), width), height)
+    })
+}
+
+/// Puts `marker` in one cell of each generated grid, e.g. a start.
+pub fn with_marker<T>(
+    grid: impl Strategy<Value = Vec<Vec<T>>>,
+    marker: T,
+) -> impl Strategy<Value = Vec<Vec<T>>>
+where
+    T: Clone + Debug,
+{
+    grid.prop_flat_map(move |grid| {
+        let (width, height) = (grid[0].len(), grid.len());
+        let marker = marker.clone();
+
+        (0..width, 0..height).prop_map(move |(x, y)| {
+            let mut grid = grid.clone();
+            grid[y][x] = marker.clone();
+            grid
+        })
+    })
+}
+
+/// Writes a grid as puzzle input, one line per row.
+#[must_use]
+pub fn text<T: Display>(grid: &[Vec<T>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().map(ToString::to_string).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_grid_is_rectangular(grid in grid(Just('.'), 1..6, 1..6)) {
+            prop_assert!((1..6).contains(&grid.len()));
+            prop_assert!(grid.iter().all(|row| row.len() == grid[0].len()));
+            prop_assert!((1..6).contains(&grid[0].len()));
+        }
+
+        #[test]
+        fn test_with_marker_places_one(grid in with_marker(grid(Just('.'), 1..6, 1..6), 'S')) {
+            prop_assert_eq!(1, grid.iter().flatten().filter(|&&cell| cell == 'S').count());
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!("ab\ncd", text(&[vec!['a', 'b'], vec!['c', 'd']]));
+        assert_eq!("12\n34", text(&[vec![1, 2], vec![3, 4]]));
+    }
+}
@@ -0,0 +1,62 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Known correct answers, keyed by year and day name:
+///
+/// ```json
+/// { "2023": { "day-01": { "part1": "142", "part2": "281" } } }
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry(BTreeMap<u16, BTreeMap<String, Answers>>);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+impl Registry {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read answers from {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid answers registry {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn get(&self, year: u16, day: &str, part: u8) -> Option<&str> {
+        let answers = self.0.get(&year)?.get(day)?;
+
+        match part {
+            1 => answers.part1.as_deref(),
+            2 => answers.part2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() -> Result<()> {
+        let registry: Registry =
+            serde_json::from_str(r#"{ "2023": { "day-01": { "part1": "142" } } }"#)?;
+
+        assert_eq!(Some("142"), registry.get(2023, "day-01", 1));
+        assert_eq!(None, registry.get(2023, "day-01", 2));
+        assert_eq!(None, registry.get(2024, "day-01", 1));
+
+        Ok(())
+    }
+}
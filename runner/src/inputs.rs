@@ -0,0 +1,149 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    measure,
+    run::PARTS,
+    workspace::{self, Day},
+};
+
+/// Result of one part on one alternate input.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub name: String,
+    pub part: u8,
+    pub status: Status,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass(String),
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// There is no expected answer for this part.
+    Unchecked(String),
+    Error(String),
+}
+
+impl Outcome {
+    #[must_use]
+    pub const fn failed(&self) -> bool {
+        matches!(self.status, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} part {}: ", self.name, self.part)?;
+
+        match &self.status {
+            Status::Pass(answer) => write!(f, "pass {answer}"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL expected {expected}, got {actual}")
+            }
+            Status::Unchecked(answer) => write!(f, "unchecked {answer}"),
+            Status::Error(error) => write!(f, "ERROR {error}"),
+        }
+    }
+}
+
+/// Reads a `name.answers` file: the first line is the answer to part 1 and
+/// the second line the answer to part 2. Empty or missing lines are unknown.
+#[must_use]
+pub fn parse_answers(content: &str) -> [Option<String>; 2] {
+    let mut lines = content.lines().map(str::trim);
+
+    PARTS.map(|_| {
+        lines
+            .next()
+            .filter(|line| !line.is_empty())
+            .map(ToString::to_string)
+    })
+}
+
+/// Runs every part of `day` against each `name.txt` in `dir`, comparing with
+/// the sibling `name.answers` file when there is one.
+pub fn run(day: &Day, dir: &Path) -> Result<Vec<Outcome>> {
+    let mut inputs = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read directory {}", dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<PathBuf>>>()?;
+    inputs.retain(|path| path.extension().is_some_and(|extension| extension == "txt"));
+    inputs.sort();
+
+    measure::build(&day.dir, &["--bins"])?;
+
+    let mut outcomes = Vec::new();
+
+    for input in inputs {
+        let name = input
+            .file_stem()
+            .context("Input has a file name")?
+            .to_string_lossy()
+            .to_string();
+
+        let answers = input.with_extension("answers");
+        let expected = if answers.exists() {
+            parse_answers(&std::fs::read_to_string(&answers)?)
+        } else {
+            [None, None]
+        };
+
+        for (part, expected) in PARTS.into_iter().zip(expected) {
+            if !day.has_part(part) {
+                continue;
+            }
+
+            let binary = workspace::target_dir(&day.dir)
+                .join("release")
+                .join(format!("part{part}"));
+
+            let status = match measure::run(&binary, &[&input]) {
+                Ok(measurement) => {
+                    let actual = measurement.stdout.trim().to_string();
+
+                    match expected {
+                        Some(expected) if expected == actual => Status::Pass(actual),
+                        Some(expected) => Status::Fail { expected, actual },
+                        None => Status::Unchecked(actual),
+                    }
+                }
+                Err(error) => Status::Error(format!("{error:#}")),
+            };
+
+            outcomes.push(Outcome {
+                name: name.clone(),
+                part,
+                status,
+            });
+        }
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("142\n281\n", [Some("142"), Some("281")])]
+    #[case("142\n", [Some("142"), None])]
+    #[case("\n281", [None, Some("281")])]
+    #[case(" 142 \r\n 281 \r\n", [Some("142"), Some("281")])]
+    #[case("", [None, None])]
+    fn test_parse_answers(#[case] content: &str, #[case] expected: [Option<&str>; 2]) {
+        assert_eq!(
+            expected.map(|answer| answer.map(ToString::to_string)),
+            parse_answers(content)
+        );
+    }
+}
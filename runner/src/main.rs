@@ -0,0 +1,182 @@
+mod answers;
+mod inputs;
+mod measure;
+mod readme;
+mod report;
+mod run;
+mod workspace;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+use crate::{answers::Registry, report::Report};
+
+/// Runs the Advent of Code solutions and reports on them.
+#[derive(Parser)]
+struct Cli {
+    /// Root of the repository, containing one directory per year.
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/.."))]
+    root: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run days and emit a JSON report.
+    Run {
+        /// Only run days of this year.
+        #[arg(long)]
+        year: Option<u16>,
+        /// Only run this day.
+        #[arg(long)]
+        day: Option<u8>,
+        /// Answers registry, relative to the root.
+        #[arg(long, default_value = "answers.json")]
+        answers: PathBuf,
+        /// Write the report here instead of stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Compare two reports for answer changes and timing regressions.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Slowdown in percent above which a part counts as a regression.
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Run one day against a directory of `name.txt`/`name.answers` pairs.
+    Inputs {
+        #[arg(long)]
+        year: u16,
+        #[arg(long)]
+        day: u8,
+        /// Directory with the alternate inputs.
+        dir: PathBuf,
+    },
+    /// Regenerate the progress tables in the marked region of the README.
+    Readme {
+        /// Answers registry, relative to the root.
+        #[arg(long, default_value = "answers.json")]
+        answers: PathBuf,
+        /// Report of a previous run used for the runtimes, relative to the root.
+        #[arg(long, default_value = "benchmarks/baseline.json")]
+        baseline: PathBuf,
+        /// Fail instead of writing when the README is out of date.
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run {
+            year,
+            day,
+            answers,
+            output,
+        } => {
+            let registry = Registry::load(&cli.root.join(answers))?;
+            let days = workspace::discover(&cli.root)?
+                .into_iter()
+                .filter(|d| year.is_none_or(|year| d.year == year))
+                .filter(|d| day.is_none_or(|day| d.number == day))
+                .collect::<Vec<_>>();
+
+            let report = run::run(&days, &registry);
+            let json = serde_json::to_string_pretty(&report)?;
+
+            match output {
+                Some(path) => std::fs::write(&path, json + "\n")
+                    .with_context(|| format!("Cannot write {}", path.display()))?,
+                None => println!("{json}"),
+            }
+
+            let failed = report
+                .results
+                .iter()
+                .any(|result| result.error.is_some() || result.matched == Some(false));
+
+            Ok(if failed {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            })
+        }
+        Command::Diff {
+            old,
+            new,
+            threshold,
+        } => {
+            let changes = report::diff(&Report::load(&old)?, &Report::load(&new)?, threshold);
+
+            for change in &changes {
+                println!("{change}");
+            }
+
+            Ok(if changes.iter().any(report::Change::is_regression) {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            })
+        }
+        Command::Inputs { year, day, dir } => {
+            let day = workspace::discover(&cli.root)?
+                .into_iter()
+                .find(|d| d.year == year && d.number == day)
+                .with_context(|| format!("No crate for {year} day {day}"))?;
+
+            let outcomes = inputs::run(&day, &dir)?;
+
+            for outcome in &outcomes {
+                println!("{outcome}");
+            }
+
+            Ok(if outcomes.iter().any(inputs::Outcome::failed) {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            })
+        }
+        Command::Readme {
+            answers,
+            baseline,
+            check,
+        } => {
+            let registry = Registry::load(&cli.root.join(answers))?;
+            let baseline = cli.root.join(baseline);
+            let baseline = if baseline.exists() {
+                Report::load(&baseline)?
+            } else {
+                Report::default()
+            };
+            let days = workspace::discover(&cli.root)?;
+
+            let path = cli.root.join("README.md");
+            let readme = std::fs::read_to_string(&path)
+                .with_context(|| format!("Cannot read {}", path.display()))?;
+            let updated = readme::update(
+                &readme,
+                &readme::render(&readme::rows(&days, &registry, &baseline)),
+            )?;
+
+            if check {
+                if updated != readme {
+                    eprintln!("{} is out of date", path.display());
+                    return Ok(ExitCode::FAILURE);
+                }
+            } else {
+                std::fs::write(&path, updated)
+                    .with_context(|| format!("Cannot write {}", path.display()))?;
+            }
+
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
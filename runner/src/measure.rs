@@ -0,0 +1,111 @@
+use std::{
+    io::Read,
+    mem::MaybeUninit,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context, Result};
+
+/// Outcome of running a solution binary to completion.
+#[derive(Debug)]
+pub struct Measurement {
+    pub stdout: String,
+    pub stderr: String,
+    pub peak_memory_kib: u64,
+}
+
+/// Runs `binary` with `args`, recording its peak resident set size as
+/// reported by `wait4`. Fails when the process does not exit cleanly.
+///
+/// The binary is asked through [`timing::ENV_VAR`] to report its parse and
+/// solve time on stderr.
+pub fn run(binary: &Path, args: &[&Path]) -> Result<Measurement> {
+    let mut child = Command::new(binary)
+        .args(args)
+        .env(timing::ENV_VAR, "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Cannot run {}", binary.display()))?;
+
+    // NOTE: stderr is drained on its own thread so neither pipe can fill up
+    // and block the child
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("Child stderr is not captured")?;
+    let stderr = std::thread::spawn(move || {
+        let mut content = String::new();
+        stderr.read_to_string(&mut content).map(|_| content)
+    });
+
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .context("Child stdout is not captured")?
+        .read_to_string(&mut stdout)?;
+
+    let stderr = stderr
+        .join()
+        .map_err(|_| anyhow::anyhow!("Reading stderr panicked"))??;
+
+    let pid = libc::pid_t::try_from(child.id())?;
+    let mut status = 0;
+    let mut usage = MaybeUninit::<libc::rusage>::zeroed();
+
+    // SAFETY: `pid` is our own child which has not been reaped yet, and both
+    // out pointers are valid for writes.
+    if unsafe { libc::wait4(pid, &raw mut status, 0, usage.as_mut_ptr()) } < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    if libc::WIFSIGNALED(status) {
+        bail!(
+            "{} was killed by signal {}",
+            binary.display(),
+            libc::WTERMSIG(status)
+        );
+    }
+
+    if libc::WEXITSTATUS(status) != 0 {
+        // NOTE: stderr is captured, so keep the first line of the error
+        let reason = stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| format!(": {line}"))
+            .unwrap_or_default();
+
+        bail!(
+            "{} exited with code {}{reason}",
+            binary.display(),
+            libc::WEXITSTATUS(status)
+        );
+    }
+
+    // SAFETY: `wait4` succeeded, so it filled in the usage.
+    let usage = unsafe { usage.assume_init() };
+
+    Ok(Measurement {
+        stdout,
+        stderr,
+        peak_memory_kib: u64::try_from(usage.ru_maxrss)?,
+    })
+}
+
+/// Runs `cargo build --release` for the crate in `dir`.
+pub fn build(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args(["build", "--release", "--quiet"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .with_context(|| format!("Cannot build {}", dir.display()))?;
+
+    if !status.success() {
+        bail!("Build of {} failed", dir.display());
+    }
+
+    Ok(())
+}
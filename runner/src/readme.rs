@@ -0,0 +1,226 @@
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use anyhow::{ensure, Result};
+
+use crate::{
+    answers::Registry,
+    report::{Key, Report},
+    run::PARTS,
+    workspace::Day,
+};
+
+pub const START: &str = "<!-- progress:start -->";
+pub const END: &str = "<!-- progress:end -->";
+
+/// Progress of one day, as shown in a row of the table.
+#[derive(Debug)]
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    pub parts: [Option<Part>; 2],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Part {
+    /// Whether the answer is recorded in the registry.
+    pub starred: bool,
+    /// Runtime of the part in the benchmark baseline, if any.
+    pub runtime: Option<Duration>,
+}
+
+pub fn rows(days: &[Day], registry: &Registry, baseline: &Report) -> Vec<Row> {
+    let runtimes = baseline
+        .results
+        .iter()
+        .filter(|result| result.error.is_none())
+        .map(|result| (result.key(), result.total_time()))
+        .collect::<BTreeMap<_, _>>();
+
+    days.iter()
+        .map(|day| Row {
+            year: day.year,
+            day: day.number,
+            parts: PARTS.map(|part| {
+                day.has_part(part).then(|| Part {
+                    starred: registry.get(day.year, &day.name(), part).is_some(),
+                    runtime: runtimes
+                        .get(&Key {
+                            year: day.year,
+                            day: day.number,
+                            part,
+                        })
+                        .copied(),
+                })
+            }),
+        })
+        .collect()
+}
+
+/// Renders one table per year, newest year first.
+#[must_use]
+pub fn render(rows: &[Row]) -> String {
+    let mut years = BTreeMap::<_, Vec<_>>::new();
+    for row in rows {
+        years.entry(row.year).or_default().push(row);
+    }
+
+    let mut out = String::new();
+
+    for (year, rows) in years.iter().rev() {
+        let parts = rows.iter().flat_map(|row| row.parts.iter().flatten());
+        let stars = parts.clone().filter(|part| part.starred).count();
+
+        let _ = writeln!(out, "## {year}\n");
+        let _ = writeln!(
+            out,
+            "{} days solved, {} parts, {stars} ⭐\n",
+            rows.len(),
+            parts.count()
+        );
+        let _ = writeln!(out, "| Day | Part 1 | Part 2 |");
+        let _ = writeln!(out, "| --- | --- | --- |");
+
+        for row in rows {
+            let name = format!("day-{:02}", row.day);
+            let [first, second] = row.parts.map(cell);
+            let _ = writeln!(out, "| [{name}]({year}/{name}) | {first} | {second} |");
+        }
+
+        out.push('\n');
+    }
+
+    out.push_str("⭐ answer recorded in `answers.json`, ✓ solved without a recorded answer.\n");
+
+    out
+}
+
+fn cell(part: Option<Part>) -> String {
+    let Some(part) = part else {
+        return String::new();
+    };
+
+    let mark = if part.starred { "⭐" } else { "✓" };
+
+    part.runtime.map_or_else(
+        || mark.to_string(),
+        |runtime| format!("{mark} {runtime:.2?}"),
+    )
+}
+
+/// Replaces the region between the markers with `content`, appending a
+/// new marked region when the README does not have one yet.
+pub fn update(readme: &str, content: &str) -> Result<String> {
+    let region = format!("{START}\n{content}{END}");
+
+    let (Some(start), Some(end)) = (readme.find(START), readme.find(END)) else {
+        ensure!(
+            !readme.contains(START) && !readme.contains(END),
+            "README has only one of the progress markers"
+        );
+
+        let separator = if readme.is_empty() || readme.ends_with("\n\n") {
+            ""
+        } else if readme.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+
+        return Ok(format!("{readme}{separator}{region}\n"));
+    };
+
+    ensure!(start < end, "README progress markers are out of order");
+
+    Ok(format!(
+        "{}{region}{}",
+        &readme[..start],
+        &readme[end + END.len()..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(starred: bool, millis: Option<u64>) -> Part {
+        Part {
+            starred,
+            runtime: millis.map(Duration::from_millis),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let rows = [
+            Row {
+                year: 2023,
+                day: 1,
+                parts: [Some(part(true, Some(2))), Some(part(false, None))],
+            },
+            Row {
+                year: 2024,
+                day: 9,
+                parts: [Some(part(true, None)), None],
+            },
+        ];
+
+        let expected = "## 2024
+
+1 days solved, 1 parts, 1 ⭐
+
+| Day | Part 1 | Part 2 |
+| --- | --- | --- |
+| [day-09](2024/day-09) | ⭐ |  |
+
+## 2023
+
+1 days solved, 2 parts, 1 ⭐
+
+| Day | Part 1 | Part 2 |
+| --- | --- | --- |
+| [day-01](2023/day-01) | ⭐ 2.00ms | ✓ |
+
+⭐ answer recorded in `answers.json`, ✓ solved without a recorded answer.
+";
+
+        assert_eq!(expected, render(&rows));
+    }
+
+    #[test]
+    fn test_update_appends_region() -> Result<()> {
+        let readme = update("# Advent of Code\n", "table\n")?;
+
+        assert_eq!(
+            format!("# Advent of Code\n\n{START}\ntable\n{END}\n"),
+            readme
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_replaces_region() -> Result<()> {
+        let readme = format!("# Advent of Code\n\n{START}\nold\n{END}\n\nFooter\n");
+
+        assert_eq!(
+            format!("# Advent of Code\n\n{START}\nnew\n{END}\n\nFooter\n"),
+            update(&readme, "new\n")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_is_idempotent() -> Result<()> {
+        let once = update("# Advent of Code\n", "table\n")?;
+
+        assert_eq!(once, update(&once, "table\n")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_rejects_single_marker() {
+        assert!(update(&format!("{START}\n"), "table\n").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable outcome of a run over one or more days.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    pub results: Vec<PartResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartResult {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Time until the part binary finished parsing, `None` when it parses
+    /// lazily while solving or did not report it.
+    pub parse_time_ns: Option<u64>,
+    /// Time from the end of parsing, or from the start of the part binary
+    /// when parsing is not reported, until it found the answer.
+    pub solve_time_ns: Option<u64>,
+    pub peak_memory_kib: u64,
+    /// Whether the answer equals the registered one, `None` when unknown.
+    pub matched: Option<bool>,
+}
+
+impl PartResult {
+    #[must_use]
+    pub const fn key(&self) -> Key {
+        Key {
+            year: self.year,
+            day: self.day,
+            part: self.part,
+        }
+    }
+
+    #[must_use]
+    pub fn total_time(&self) -> Duration {
+        Duration::from_nanos(
+            self.parse_time_ns.unwrap_or_default() + self.solve_time_ns.unwrap_or_default(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Key {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} day-{:02} part {}", self.year, self.day, self.part)
+    }
+}
+
+impl Report {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read report {}", path.display()))?;
+
+        serde_json::from_str(&content).with_context(|| format!("Invalid report {}", path.display()))
+    }
+}
+
+/// Timing changes smaller than this are considered noise.
+const NOISE: Duration = Duration::from_millis(1);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    Answer {
+        key: Key,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    Slower {
+        key: Key,
+        old: Duration,
+        new: Duration,
+    },
+    Added(Key),
+    Removed(Key),
+}
+
+impl Change {
+    /// Whether this change should fail a CI run.
+    #[must_use]
+    pub const fn is_regression(&self) -> bool {
+        matches!(self, Self::Answer { .. } | Self::Slower { .. })
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |answer: &Option<String>| answer.clone().unwrap_or_else(|| "<none>".into());
+
+        match self {
+            Self::Answer { key, old, new } => {
+                write!(f, "{key}: answer changed {} -> {}", show(old), show(new))
+            }
+            Self::Slower { key, old, new } => write!(
+                f,
+                "{key}: slower {old:.2?} -> {new:.2?} (+{:.0}%)",
+                (new.as_secs_f64() / old.as_secs_f64() - 1.0) * 100.0
+            ),
+            Self::Added(key) => write!(f, "{key}: only in new report"),
+            Self::Removed(key) => write!(f, "{key}: only in old report"),
+        }
+    }
+}
+
+fn index(report: &Report) -> BTreeMap<Key, &PartResult> {
+    report
+        .results
+        .iter()
+        .map(|result| (result.key(), result))
+        .collect()
+}
+
+/// Compares two reports part by part. A part is slower when its total time
+/// grew by more than `threshold` percent and by more than the noise floor.
+#[must_use]
+pub fn diff(old: &Report, new: &Report, threshold: f64) -> Vec<Change> {
+    let old = index(old);
+    let new = index(new);
+
+    let mut changes = Vec::new();
+
+    for (key, old) in &old {
+        let Some(new) = new.get(key) else {
+            changes.push(Change::Removed(*key));
+            continue;
+        };
+
+        if old.answer != new.answer {
+            changes.push(Change::Answer {
+                key: *key,
+                old: old.answer.clone(),
+                new: new.answer.clone(),
+            });
+        }
+
+        let (old, new) = (old.total_time(), new.total_time());
+        if new > old + NOISE && new.as_secs_f64() > old.as_secs_f64() * (1.0 + threshold / 100.0) {
+            changes.push(Change::Slower {
+                key: *key,
+                old,
+                new,
+            });
+        }
+    }
+
+    changes.extend(
+        new.keys()
+            .filter(|key| !old.contains_key(key))
+            .map(|key| Change::Added(*key)),
+    );
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn result(part: u8, answer: &str, millis: u64) -> PartResult {
+        PartResult {
+            year: 2023,
+            day: 1,
+            part,
+            answer: Some(answer.to_string()),
+            error: None,
+            parse_time_ns: Some(0),
+            solve_time_ns: Some(millis * 1_000_000),
+            peak_memory_kib: 2048,
+            matched: None,
+        }
+    }
+
+    const fn key(part: u8) -> Key {
+        Key {
+            year: 2023,
+            day: 1,
+            part,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<()> {
+        let report = Report {
+            results: vec![result(1, "142", 3)],
+        };
+
+        let json = serde_json::to_string(&report)?;
+
+        assert_eq!(report, serde_json::from_str(&json)?);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("142", 10, 10, vec![])]
+    #[case("143", 10, 10, vec![Change::Answer { key: key(1), old: Some("142".into()), new: Some("143".into()) }])]
+    #[case("142", 10, 12, vec![Change::Slower { key: key(1), old: Duration::from_millis(10), new: Duration::from_millis(12) }])]
+    #[case("142", 10, 11, vec![])]
+    #[case("142", 0, 1, vec![])]
+    #[case("142", 10, 5, vec![])]
+    fn test_diff(
+        #[case] answer: &str,
+        #[case] old_millis: u64,
+        #[case] new_millis: u64,
+        #[case] expected: Vec<Change>,
+    ) {
+        let old = Report {
+            results: vec![result(1, "142", old_millis)],
+        };
+        let new = Report {
+            results: vec![result(1, answer, new_millis)],
+        };
+
+        assert_eq!(expected, diff(&old, &new, 10.0));
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let old = Report {
+            results: vec![result(1, "142", 1)],
+        };
+        let new = Report {
+            results: vec![result(2, "281", 1)],
+        };
+
+        assert_eq!(
+            vec![Change::Removed(key(1)), Change::Added(key(2))],
+            diff(&old, &new, 10.0)
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use crate::{
+    answers::Registry,
+    measure,
+    report::{PartResult, Report},
+    workspace::{self, Day},
+};
+
+pub const PARTS: [u8; 2] = [1, 2];
+
+/// Builds and runs both parts of every day. Part binaries time their own
+/// parsing and solving, leaving out the cost of starting the process.
+pub fn run(days: &[Day], registry: &Registry) -> Report {
+    let mut report = Report::default();
+
+    for day in days {
+        if let Err(error) = measure::build(&day.dir, &["--bins"]) {
+            report.results.extend(
+                PARTS
+                    .into_iter()
+                    .filter(|part| day.has_part(*part))
+                    .map(|part| failed(day, part, &error)),
+            );
+            continue;
+        }
+
+        for part in PARTS.into_iter().filter(|part| day.has_part(*part)) {
+            let binary = workspace::target_dir(&day.dir)
+                .join("release")
+                .join(format!("part{part}"));
+
+            let result = match measure::run(&binary, &[]) {
+                Ok(measurement) => {
+                    let answer = measurement.stdout.trim().to_string();
+
+                    PartResult {
+                        year: day.year,
+                        day: day.number,
+                        part,
+                        matched: registry
+                            .get(day.year, &day.name(), part)
+                            .map(|expected| expected == answer),
+                        answer: Some(answer),
+                        error: None,
+                        parse_time_ns: timing::parse_time(&measurement.stderr).map(nanos),
+                        solve_time_ns: timing::solve_time(&measurement.stderr).map(nanos),
+                        peak_memory_kib: measurement.peak_memory_kib,
+                    }
+                }
+                Err(error) => failed(day, part, &error),
+            };
+
+            report.results.push(result);
+        }
+    }
+
+    report
+}
+
+fn failed(day: &Day, part: u8, error: &anyhow::Error) -> PartResult {
+    PartResult {
+        year: day.year,
+        day: day.number,
+        part,
+        answer: None,
+        error: Some(format!("{error:#}")),
+        parse_time_ns: None,
+        solve_time_ns: None,
+        peak_memory_kib: 0,
+        matched: None,
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// A puzzle crate found in the repository, e.g. `2023/day-01`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Day {
+    pub year: u16,
+    pub number: u8,
+    pub dir: PathBuf,
+}
+
+impl Day {
+    #[must_use]
+    pub fn name(&self) -> String {
+        format!("day-{:02}", self.number)
+    }
+
+    /// Whether the crate has a `partN` binary for `part`.
+    #[must_use]
+    pub fn has_part(&self, part: u8) -> bool {
+        self.dir
+            .join("src")
+            .join("bin")
+            .join(format!("part{part}.rs"))
+            .is_file()
+    }
+}
+
+/// Finds every `<year>/day-<nn>` crate below `root`, sorted by year and day.
+pub fn discover(root: &Path) -> Result<Vec<Day>> {
+    let mut days = Vec::new();
+
+    for year in read_dir(root)? {
+        let Some(number) = file_name(&year).and_then(|name| name.parse().ok()) else {
+            continue;
+        };
+
+        for dir in read_dir(&year)? {
+            let Some(day) = file_name(&dir)
+                .and_then(|name| name.strip_prefix("day-"))
+                .and_then(|day| day.parse().ok())
+            else {
+                continue;
+            };
+
+            if dir.join("Cargo.toml").is_file() {
+                days.push(Day {
+                    year: number,
+                    number: day,
+                    dir,
+                });
+            }
+        }
+    }
+
+    days.sort();
+
+    Ok(days)
+}
+
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read directory {}", dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .filter(|path| path.as_ref().map_or(true, |path: &PathBuf| path.is_dir()))
+        .collect()
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name()?.to_str()
+}
+
+/// Directory cargo writes the artifacts of the crate in `dir` to.
+#[must_use]
+pub fn target_dir(dir: &Path) -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| dir.join("target"), PathBuf::from)
+}
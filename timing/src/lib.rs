@@ -0,0 +1,99 @@
+//! Lets a part binary report how long parsing and solving took within the
+//! same run, so the runner can time them without the cost of starting the
+//! process.
+
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// Set by the runner to ask part binaries for their parse and solve time.
+pub const ENV_VAR: &str = "AOC_PARSE_TIME";
+
+const PARSE_PREFIX: &str = "parse_time_ns=";
+const SOLVE_PREFIX: &str = "solve_time_ns=";
+
+static START: OnceLock<Instant> = OnceLock::new();
+static PARSED: OnceLock<Instant> = OnceLock::new();
+
+/// Marks the start of the run, before the input is read.
+pub fn start() {
+    START.get_or_init(Instant::now);
+}
+
+/// Marks the end of parsing, printing the time since [`start`] to stderr when
+/// the runner asked for it. Does nothing outside of a run, e.g. in tests.
+///
+/// Binaries that parse lazily while solving do not call it, so no parse time
+/// is reported for them.
+pub fn parsed() {
+    let Some(start) = START.get() else {
+        return;
+    };
+    let parsed = PARSED.get_or_init(Instant::now);
+
+    report(PARSE_PREFIX, parsed.duration_since(*start));
+}
+
+/// Marks the end of solving, printing the time since [`parsed`], or since
+/// [`start`] when parsing was not marked, to stderr when the runner asked for
+/// it. Does nothing outside of a run, e.g. in tests.
+pub fn solved() {
+    let Some(start) = PARSED.get().or_else(|| START.get()) else {
+        return;
+    };
+
+    report(SOLVE_PREFIX, start.elapsed());
+}
+
+fn report(prefix: &str, duration: Duration) {
+    if std::env::var_os(ENV_VAR).is_some() {
+        eprintln!("{prefix}{}", duration.as_nanos());
+    }
+}
+
+/// Finds the parse time a part binary printed to stderr.
+#[must_use]
+pub fn parse_time(stderr: &str) -> Option<Duration> {
+    find(stderr, PARSE_PREFIX)
+}
+
+/// Finds the solve time a part binary printed to stderr.
+#[must_use]
+pub fn solve_time(stderr: &str) -> Option<Duration> {
+    find(stderr, SOLVE_PREFIX)
+}
+
+fn find(stderr: &str, prefix: &str) -> Option<Duration> {
+    stderr
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix))
+        .and_then(|nanos| nanos.parse().ok())
+        .map(Duration::from_nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("parse_time_ns=1500\n", Some(Duration::from_nanos(1500)))]
+    #[case("warning\nparse_time_ns=7\n", Some(Duration::from_nanos(7)))]
+    #[case("", None)]
+    #[case("parse_time_ns=soon\n", None)]
+    #[case("Error: parse_time_ns=3\n", None)]
+    #[case("solve_time_ns=3\n", None)]
+    fn test_parse_time(#[case] stderr: &str, #[case] expected: Option<Duration>) {
+        assert_eq!(expected, parse_time(stderr));
+    }
+
+    #[rstest]
+    #[case("parse_time_ns=2\nsolve_time_ns=40\n", Some(Duration::from_nanos(40)))]
+    #[case("solve_time_ns=9\n", Some(Duration::from_nanos(9)))]
+    #[case("parse_time_ns=2\n", None)]
+    fn test_solve_time(#[case] stderr: &str, #[case] expected: Option<Duration>) {
+        assert_eq!(expected, solve_time(stderr));
+    }
+}